@@ -0,0 +1,7 @@
+-- dictionary_info
+ALTER TABLE dictionary_info ADD COLUMN version TEXT;
+ALTER TABLE dictionary_info ADD COLUMN author TEXT;
+ALTER TABLE dictionary_info ADD COLUMN license TEXT;
+ALTER TABLE dictionary_info ADD COLUMN source_url TEXT;
+ALTER TABLE dictionary_info ADD COLUMN created_at TEXT;
+ALTER TABLE dictionary_info ADD COLUMN min_schema_version INTEGER;
@@ -0,0 +1,14 @@
+-- kanji
+-- Kanji metadata from KANJIDIC2. jlpt keeps the KANJIDIC2 levels from 4 (easiest) to 1.
+-- Readings are comma separated as written in KANJIDIC2, meanings are separated by ␞
+CREATE TABLE IF NOT EXISTS kanji (
+	id INTEGER PRIMARY KEY NOT NULL,
+	kanji TEXT NOT NULL,
+	jlpt INTEGER,
+	grade INTEGER,
+	stroke_count INTEGER,
+	on_readings TEXT NOT NULL,
+	kun_readings TEXT NOT NULL,
+	meanings TEXT NOT NULL
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_kanji_1 ON kanji (kanji);
\ No newline at end of file
@@ -0,0 +1,8 @@
+-- word_reading_tag
+-- JMdict re_inf tags of a reading and ke_inf tags of the word it belongs to
+CREATE TABLE IF NOT EXISTS word_reading_tag (
+	word_reading_id INTEGER NOT NULL,
+	tag TEXT NOT NULL,
+	FOREIGN KEY(word_reading_id) REFERENCES word_reading(id),
+	PRIMARY KEY (word_reading_id, tag)
+);
\ No newline at end of file
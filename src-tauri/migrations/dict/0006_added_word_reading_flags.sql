@@ -0,0 +1,11 @@
+-- word_reading
+-- JMdict re_pri marks common readings, re_inf marks rare (rk, ok, sk) and irregular (ik) ones.
+-- Pitch accents are comma separated downstep positions in morae, 0 for heiban
+ALTER TABLE word_reading
+ADD COLUMN is_common BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE word_reading
+ADD COLUMN is_rare BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE word_reading
+ADD COLUMN is_irregular BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE word_reading
+ADD COLUMN pitch_accent TEXT;
\ No newline at end of file
@@ -0,0 +1,59 @@
+-- card_fsrs
+CREATE TABLE IF NOT EXISTS card_fsrs_new (
+	id INTEGER PRIMARY KEY NOT NULL,
+	user_id INTEGER NOT NULL,
+	dictionary_id INTEGER NOT NULL,
+	word TEXT NOT NULL,
+	due DATETIME NOT NULL,
+	stability REAL NOT NULL,
+	difficulty REAL NOT NULL,
+	elapsed_days INTEGER NOT NULL,
+	scheduled_days INTEGER NOT NULL,
+	reps INTEGER NOT NULL,
+	lapses INTEGER NOT NULL,
+	state INTEGER NOT NULL DEFAULT 0,
+	last_review DATETIME NOT NULL,
+	created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	FOREIGN KEY(user_id) REFERENCES user(id),
+	FOREIGN KEY(dictionary_id) REFERENCES dictionary(id),
+	UNIQUE(user_id, dictionary_id, word)
+);
+-- Old cards were shared by everyone, so give a copy to every user and dictionary that answered the word
+INSERT INTO card_fsrs_new (
+		user_id,
+		dictionary_id,
+		word,
+		due,
+		stability,
+		difficulty,
+		elapsed_days,
+		scheduled_days,
+		reps,
+		lapses,
+		state,
+		last_review,
+		created_at,
+		updated_at
+	)
+SELECT DISTINCT answer_stats.user_id,
+	game_stats.dictionary_id,
+	card_fsrs.word,
+	card_fsrs.due,
+	card_fsrs.stability,
+	card_fsrs.difficulty,
+	card_fsrs.elapsed_days,
+	card_fsrs.scheduled_days,
+	card_fsrs.reps,
+	card_fsrs.lapses,
+	card_fsrs.state,
+	card_fsrs.last_review,
+	card_fsrs.created_at,
+	card_fsrs.updated_at
+FROM card_fsrs
+	JOIN answer_stats ON answer_stats.word = card_fsrs.word
+	JOIN game_stats ON answer_stats.game_stats_id = game_stats.id;
+DROP INDEX IF EXISTS idx_card_fsrs_1;
+DROP TABLE card_fsrs;
+ALTER TABLE card_fsrs_new RENAME TO card_fsrs;
+CREATE INDEX idx_card_fsrs_1 ON card_fsrs (user_id, dictionary_id, due);
@@ -0,0 +1,8 @@
+-- fsrs_settings
+CREATE TABLE IF NOT EXISTS fsrs_settings (
+	user_id INTEGER PRIMARY KEY NOT NULL,
+	easy_threshold REAL NOT NULL,
+	hard_threshold REAL NOT NULL,
+	updated_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+	FOREIGN KEY(user_id) REFERENCES user(id)
+);
@@ -0,0 +1,2 @@
+-- fsrs_settings
+ALTER TABLE fsrs_settings ADD COLUMN parameters TEXT;
@@ -0,0 +1,24 @@
+-- review_log
+CREATE TABLE IF NOT EXISTS review_log (
+	id INTEGER PRIMARY KEY NOT NULL,
+	user_id INTEGER NOT NULL,
+	dictionary_id INTEGER NOT NULL,
+	word TEXT NOT NULL,
+	answer_stats_id INTEGER,
+	rating INTEGER NOT NULL,
+	elapsed_days INTEGER NOT NULL,
+	previous_state INTEGER NOT NULL,
+	previous_due DATETIME NOT NULL,
+	previous_stability REAL NOT NULL,
+	previous_difficulty REAL NOT NULL,
+	state INTEGER NOT NULL,
+	due DATETIME NOT NULL,
+	stability REAL NOT NULL,
+	difficulty REAL NOT NULL,
+	scheduled_days INTEGER NOT NULL,
+	reviewed_at DATETIME NOT NULL,
+	FOREIGN KEY(user_id) REFERENCES user(id),
+	FOREIGN KEY(dictionary_id) REFERENCES dictionary(id),
+	FOREIGN KEY(answer_stats_id) REFERENCES answer_stats(id)
+);
+CREATE INDEX idx_review_log_1 ON review_log (user_id, dictionary_id, word);
@@ -0,0 +1,4 @@
+-- card_fsrs
+ALTER TABLE card_fsrs ADD COLUMN is_suspended BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE card_fsrs ADD COLUMN buried_until DATETIME;
+ALTER TABLE card_fsrs ADD COLUMN note TEXT;
@@ -0,0 +1,7 @@
+-- dictionary
+ALTER TABLE dictionary ADD COLUMN version TEXT;
+ALTER TABLE dictionary ADD COLUMN author TEXT;
+ALTER TABLE dictionary ADD COLUMN license TEXT;
+ALTER TABLE dictionary ADD COLUMN source_url TEXT;
+ALTER TABLE dictionary ADD COLUMN created_at TEXT;
+ALTER TABLE dictionary ADD COLUMN min_schema_version INTEGER;
@@ -0,0 +1,9 @@
+-- word_search
+CREATE VIRTUAL TABLE IF NOT EXISTS word_search USING fts5(
+	word,
+	readings,
+	romaji,
+	meanings,
+	dictionary_id UNINDEXED,
+	tokenize = 'trigram'
+);
@@ -0,0 +1,15 @@
+-- word_sense
+-- Tags of the senses in word.meanings, matched by meaning block and sense position.
+-- Tags are comma separated JMdict codes, priority comes from the entry's ke_pri
+CREATE TABLE IF NOT EXISTS word_sense (
+	id INTEGER PRIMARY KEY NOT NULL,
+	word_id INTEGER NOT NULL,
+	entry_index INTEGER NOT NULL,
+	sense_index INTEGER NOT NULL,
+	pos TEXT NOT NULL,
+	misc TEXT NOT NULL,
+	field TEXT NOT NULL,
+	priority TEXT NOT NULL,
+	FOREIGN KEY(word_id) REFERENCES word(id)
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_word_sense_1 ON word_sense (word_id, entry_index, sense_index);
@@ -0,0 +1,44 @@
+-- kanji
+-- Kanji metadata from KANJIDIC2. jlpt keeps the KANJIDIC2 levels from 4 (easiest) to 1.
+-- Readings are comma separated as written in KANJIDIC2, meanings are separated by ␞
+CREATE TABLE IF NOT EXISTS kanji (
+	id INTEGER PRIMARY KEY NOT NULL,
+	kanji TEXT NOT NULL,
+	jlpt INTEGER,
+	grade INTEGER,
+	stroke_count INTEGER,
+	on_readings TEXT NOT NULL,
+	kun_readings TEXT NOT NULL,
+	meanings TEXT NOT NULL,
+	dictionary_id INTEGER NOT NULL,
+	FOREIGN KEY(dictionary_id) REFERENCES dictionary(id)
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_kanji_1 ON kanji (kanji, dictionary_id);
+-- word_kanji
+-- Kanji of each word, so kanji filters do not split words at query time
+CREATE TABLE IF NOT EXISTS word_kanji (
+	word_id INTEGER NOT NULL,
+	kanji TEXT NOT NULL,
+	FOREIGN KEY(word_id) REFERENCES word(id),
+	PRIMARY KEY (word_id, kanji)
+);
+WITH RECURSIVE word_chars(word_id, kanji, rest) AS (
+	SELECT id,
+		'',
+		word
+	FROM word
+	UNION ALL
+	SELECT word_id,
+		substr(rest, 1, 1),
+		substr(rest, 2)
+	FROM word_chars
+	WHERE rest <> ''
+)
+INSERT OR IGNORE INTO word_kanji (word_id, kanji)
+SELECT word_id,
+	kanji
+FROM word_chars
+WHERE unicode(kanji) BETWEEN 13312 AND 19903
+	OR unicode(kanji) BETWEEN 19968 AND 40959
+	OR unicode(kanji) BETWEEN 63744 AND 64255
+	OR unicode(kanji) BETWEEN 131072 AND 201551;
\ No newline at end of file
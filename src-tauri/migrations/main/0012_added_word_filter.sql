@@ -0,0 +1,12 @@
+-- word_reading_tag
+-- JMdict re_inf tags of a reading and ke_inf tags of the word it belongs to
+CREATE TABLE IF NOT EXISTS word_reading_tag (
+	word_reading_id INTEGER NOT NULL,
+	tag TEXT NOT NULL,
+	FOREIGN KEY(word_reading_id) REFERENCES word_reading(id),
+	PRIMARY KEY (word_reading_id, tag)
+);
+-- game_stats
+-- Full word filter of the game as JSON, criteria left at their defaults are omitted
+ALTER TABLE game_stats
+ADD COLUMN word_filter TEXT;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- game_stats
+-- Seed of the word order, NULL for games with random order
+ALTER TABLE game_stats
+ADD COLUMN seed INTEGER;
\ No newline at end of file
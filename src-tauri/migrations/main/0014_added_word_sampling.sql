@@ -0,0 +1,4 @@
+-- game_stats
+-- Word sampling biases of the game as JSON, NULL for uniform selection
+ALTER TABLE game_stats
+ADD COLUMN word_sampling TEXT;
\ No newline at end of file
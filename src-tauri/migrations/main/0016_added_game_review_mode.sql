@@ -0,0 +1,4 @@
+-- game_stats
+-- Whether the game served due cards instead of sampled words
+ALTER TABLE game_stats
+ADD COLUMN is_review_mode BOOLEAN NOT NULL DEFAULT false;
\ No newline at end of file
@@ -0,0 +1,5 @@
+INSERT INTO card_fsrs (
+    user_id, dictionary_id, word, due, stability, difficulty, elapsed_days,
+    scheduled_days, reps, lapses, state, last_review
+) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(user_id, dictionary_id, word) DO NOTHING
\ No newline at end of file
@@ -6,7 +6,11 @@ INSERT INTO game_stats (
 		word_part,
 		word_part_reading,
 		font_id,
-		dictionary_id
+		dictionary_id,
+		word_filter,
+		seed,
+		word_sampling,
+		is_review_mode
 	)
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
 RETURNING id;
\ No newline at end of file
@@ -0,0 +1,36 @@
+INSERT INTO review_log (
+		user_id,
+		dictionary_id,
+		word,
+		answer_stats_id,
+		rating,
+		elapsed_days,
+		previous_state,
+		previous_due,
+		previous_stability,
+		previous_difficulty,
+		state,
+		due,
+		stability,
+		difficulty,
+		scheduled_days,
+		reviewed_at
+	)
+VALUES (
+		$1,
+		$2,
+		$3,
+		$4,
+		$5,
+		$6,
+		$7,
+		$8,
+		$9,
+		$10,
+		$11,
+		$12,
+		$13,
+		$14,
+		$15,
+		$16
+	);
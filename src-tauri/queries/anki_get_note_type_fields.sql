@@ -0,0 +1,7 @@
+SELECT nt.id,
+	nt.name,
+	f.name
+FROM notetypes nt
+	JOIN fields f ON f.ntid = nt.id
+ORDER BY nt.id,
+	f.ord;
\ No newline at end of file
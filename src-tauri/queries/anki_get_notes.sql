@@ -0,0 +1,5 @@
+SELECT id,
+	flds
+FROM notes
+WHERE mid = $1
+ORDER BY id;
\ No newline at end of file
@@ -0,0 +1,10 @@
+SELECT c.nid,
+	r.id,
+	r.ease
+FROM revlog r
+	JOIN cards c ON c.id = r.cid
+	JOIN notes n ON n.id = c.nid
+WHERE n.mid = $1
+	AND r.ease BETWEEN 1 AND 4
+	AND r.type IN (0, 1, 2)
+ORDER BY r.id;
\ No newline at end of file
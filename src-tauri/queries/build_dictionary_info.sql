@@ -0,0 +1,13 @@
+INSERT INTO dictionary_info (
+		guid,
+		name,
+		stats_config,
+		description,
+		version,
+		author,
+		license,
+		source_url,
+		created_at,
+		min_schema_version
+	)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);
\ No newline at end of file
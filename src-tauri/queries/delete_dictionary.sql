@@ -9,6 +9,14 @@ WHERE word_reading_id IN (
 DELETE FROM word_part_reading
 WHERE dictionary_id = ?;
 
+DELETE FROM word_reading_tag
+WHERE word_reading_id IN (
+        SELECT wr.id
+        FROM word_reading wr
+            JOIN word w ON w.id = wr.word_id
+        WHERE w.dictionary_id = ?
+    );
+
 DELETE FROM word_reading
 WHERE word_id IN (
         SELECT id
@@ -16,6 +24,26 @@ WHERE word_id IN (
         WHERE dictionary_id = ?
     );
 
+DELETE FROM word_sense
+WHERE word_id IN (
+        SELECT id
+        FROM word
+        WHERE dictionary_id = ?
+    );
+
+DELETE FROM word_kanji
+WHERE word_id IN (
+        SELECT id
+        FROM word
+        WHERE dictionary_id = ?
+    );
+
+DELETE FROM kanji
+WHERE dictionary_id = ?;
+
+DELETE FROM word_search
+WHERE dictionary_id = ?;
+
 DELETE FROM word
 WHERE dictionary_id = ?;
 
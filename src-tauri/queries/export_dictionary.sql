@@ -0,0 +1,107 @@
+INSERT INTO dict_db.dictionary_info (
+		guid,
+		name,
+		stats_config,
+		description,
+		version,
+		author,
+		license,
+		source_url,
+		created_at,
+		min_schema_version
+	)
+SELECT guid,
+	name,
+	stats_config,
+	description,
+	version,
+	author,
+	license,
+	source_url,
+	created_at,
+	min_schema_version
+FROM main.dictionary
+WHERE id = $1;
+INSERT INTO dict_db.word (id, word, frequency, meanings)
+SELECT id,
+	word,
+	frequency,
+	meanings
+FROM main.word
+WHERE dictionary_id = $1;
+INSERT INTO dict_db.word_reading (
+		id,
+		word_id,
+		word_reading,
+		is_common,
+		is_rare,
+		is_irregular,
+		pitch_accent
+	)
+SELECT wr.id,
+	wr.word_id,
+	wr.word_reading,
+	wr.is_common,
+	wr.is_rare,
+	wr.is_irregular,
+	wr.pitch_accent
+FROM main.word_reading wr
+	JOIN main.word w ON w.id = wr.word_id
+WHERE w.dictionary_id = $1;
+INSERT INTO dict_db.word_part_reading (id, word_part, word_part_reading)
+SELECT id,
+	word_part,
+	word_part_reading
+FROM main.word_part_reading
+WHERE dictionary_id = $1;
+INSERT INTO dict_db.word_reading_word_part_reading (word_reading_id, word_part_reading_id)
+SELECT wrwpr.word_reading_id,
+	wrwpr.word_part_reading_id
+FROM main.word_reading_word_part_reading wrwpr
+	JOIN main.word_reading wr ON wr.id = wrwpr.word_reading_id
+	JOIN main.word w ON w.id = wr.word_id
+WHERE w.dictionary_id = $1;
+INSERT INTO dict_db.word_sense (
+		word_id,
+		entry_index,
+		sense_index,
+		pos,
+		misc,
+		field,
+		priority
+	)
+SELECT ws.word_id,
+	ws.entry_index,
+	ws.sense_index,
+	ws.pos,
+	ws.misc,
+	ws.field,
+	ws.priority
+FROM main.word_sense ws
+	JOIN main.word w ON w.id = ws.word_id
+WHERE w.dictionary_id = $1;
+INSERT INTO dict_db.kanji (
+		kanji,
+		jlpt,
+		grade,
+		stroke_count,
+		on_readings,
+		kun_readings,
+		meanings
+	)
+SELECT kanji,
+	jlpt,
+	grade,
+	stroke_count,
+	on_readings,
+	kun_readings,
+	meanings
+FROM main.kanji
+WHERE dictionary_id = $1;
+INSERT INTO dict_db.word_reading_tag (word_reading_id, tag)
+SELECT wrt.word_reading_id,
+	wrt.tag
+FROM main.word_reading_tag wrt
+	JOIN main.word_reading wr ON wr.id = wrt.word_reading_id
+	JOIN main.word w ON w.id = wr.word_id
+WHERE w.dictionary_id = $1;
\ No newline at end of file
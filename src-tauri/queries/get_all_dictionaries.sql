@@ -1 +1 @@
-SELECT id, guid, name, is_exist, stats_config, description FROM dictionary;
+SELECT id, guid, name, is_exist, stats_config, description, version, author, license, source_url, created_at, min_schema_version FROM dictionary;
\ No newline at end of file
@@ -30,6 +30,12 @@ WITH RankedAnswers AS (
         AND ans.user_id = $4
         AND gs.dictionary_id = $5
         AND gs.word_part IS NULL
+        AND (
+            gs.word_filter IS NULL
+            OR json_remove(gs.word_filter, '$.minFrequency', '$.maxFrequency') = '{}'
+        )
+        AND gs.word_sampling IS NULL
+        AND NOT gs.is_review_mode
 )
 SELECT game_id,
     COUNT(*) AS length
@@ -11,4 +11,4 @@ SELECT
     state,
     last_review as "last_review: NaiveDateTime"
 FROM card_fsrs
-WHERE word = ?
\ No newline at end of file
+WHERE user_id = ? AND dictionary_id = ? AND word = ?
\ No newline at end of file
@@ -0,0 +1,20 @@
+SELECT id,
+	answer_stats_id,
+	rating,
+	elapsed_days,
+	previous_state,
+	previous_due AS "previous_due: NaiveDateTime",
+	previous_stability,
+	previous_difficulty,
+	state,
+	due AS "due: NaiveDateTime",
+	stability,
+	difficulty,
+	scheduled_days,
+	reviewed_at AS "reviewed_at: NaiveDateTime"
+FROM review_log
+WHERE user_id = $1
+	AND dictionary_id = $2
+	AND word = $3
+ORDER BY reviewed_at,
+	id;
@@ -0,0 +1,14 @@
+SELECT
+    id as "id: i64",
+    word,
+    due as "due: NaiveDateTime",
+    stability,
+    difficulty,
+    elapsed_days,
+    scheduled_days,
+    reps,
+    lapses,
+    state,
+    last_review as "last_review: NaiveDateTime"
+FROM card_fsrs
+WHERE user_id = ? AND dictionary_id = ?
\ No newline at end of file
@@ -0,0 +1,27 @@
+SELECT d.id,
+	d.guid,
+	d.name,
+	d.description,
+	(
+		SELECT COUNT(*)
+		FROM game_stats gs
+		WHERE gs.dictionary_id = d.id
+	) AS "game_stats_count!: i64",
+	(
+		SELECT COUNT(*)
+		FROM answer_stats a
+			JOIN game_stats gs ON gs.id = a.game_stats_id
+		WHERE gs.dictionary_id = d.id
+	) AS "answer_stats_count!: i64",
+	(
+		SELECT COUNT(*)
+		FROM card_fsrs c
+		WHERE c.dictionary_id = d.id
+	) AS "cards_count!: i64",
+	(
+		SELECT COUNT(*)
+		FROM review_log r
+		WHERE r.dictionary_id = d.id
+	) AS "review_log_count!: i64"
+FROM dictionary d
+WHERE NOT d.is_exist;
\ No newline at end of file
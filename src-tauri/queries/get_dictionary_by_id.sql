@@ -0,0 +1 @@
+SELECT id, guid, name, is_exist, stats_config, description, version, author, license, source_url, created_at, min_schema_version FROM dictionary WHERE id = $1;
\ No newline at end of file
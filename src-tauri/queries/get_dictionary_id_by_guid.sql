@@ -0,0 +1,4 @@
+SELECT id
+FROM dictionary
+WHERE guid = $1
+	AND is_exist = true;
\ No newline at end of file
@@ -0,0 +1,199 @@
+WITH filtered_words AS (
+	SELECT id,
+		word,
+		frequency
+	FROM word
+	WHERE (
+			frequency IS NULL
+			OR frequency >= $1
+		)
+		AND (
+			$2 IS NULL
+			OR frequency <= $2
+		)
+		AND (
+			dictionary_id = $3
+		)
+		AND (
+			$8 IS NULL
+			OR length(word) >= $8
+		)
+		AND (
+			$9 IS NULL
+			OR length(word) <= $9
+		)
+		AND (
+			json_array_length($6) = 0
+			OR word IN (
+				SELECT value
+				FROM json_each($6)
+			)
+		)
+		AND word NOT IN (
+			SELECT value
+			FROM json_each($7)
+		)
+		AND NOT EXISTS (
+			SELECT 1
+			FROM json_each($5) ewp
+			WHERE instr(word.word, ewp.value) > 0
+		)
+		AND (
+			(
+				$12 IS NULL
+				AND $13 IS NULL
+			)
+			OR (
+				SELECT COUNT(*)
+				FROM word_reading wr
+				WHERE wr.word_id = word.id
+			) BETWEEN IFNULL($12, 0) AND IFNULL($13, 9223372036854775807)
+		)
+		AND (
+			$14 IS NULL
+			OR EXISTS (
+				SELECT 1
+				FROM word_reading wr
+					JOIN word_reading_tag wrt ON wrt.word_reading_id = wr.id
+				WHERE wr.word_id = word.id
+					AND wrt.tag IN ('ateji', 'iK', 'io', 'ik', 'gikun')
+			) = $14
+		)
+		AND (
+			(
+				$15 IS NULL
+				AND $16 IS NULL
+			)
+			OR NOT EXISTS (
+				SELECT 1
+				FROM word_kanji wk
+					LEFT JOIN kanji k ON k.kanji = wk.kanji
+					AND k.dictionary_id = word.dictionary_id
+				WHERE wk.word_id = word.id
+					AND (
+						(
+							$15 IS NOT NULL
+							AND (
+								k.jlpt IS NULL
+								OR k.jlpt < $15
+							)
+						)
+						OR (
+							$16 IS NOT NULL
+							AND (
+								k.grade IS NULL
+								OR k.grade > $16
+							)
+						)
+					)
+			)
+		)
+),
+filtered_word_readings AS (
+	SELECT wr.id,
+		wr.word_id,
+		wr.word_reading
+	FROM word_reading wr
+		JOIN filtered_words fw ON fw.id = wr.word_id
+	WHERE (
+			$10 IS NULL
+			OR length(wr.word_reading) >= $10
+		)
+		AND (
+			$11 IS NULL
+			OR length(wr.word_reading) <= $11
+		)
+		AND NOT EXISTS (
+			SELECT 1
+			FROM json_each($4) wp
+			WHERE NOT EXISTS (
+					SELECT 1
+					FROM word_reading_word_part_reading wrwpr
+						JOIN word_part_reading wpr ON wpr.id = wrwpr.word_part_reading_id
+					WHERE wrwpr.word_reading_id = wr.id
+						AND wpr.word_part = json_extract(wp.value, '$.wordPart')
+						AND (
+							json_extract(wp.value, '$.wordPartReading') IS NULL
+							OR wpr.word_part_reading = json_extract(wp.value, '$.wordPartReading')
+						)
+				)
+		)
+),
+due_words AS (
+	SELECT w.id,
+		w.word,
+		w.meanings,
+		c.due
+	FROM card_fsrs c
+		JOIN word w ON w.word = c.word
+		AND w.dictionary_id = c.dictionary_id
+	WHERE c.user_id = $17
+		AND c.dictionary_id = $3
+		AND c.due <= $19
+		AND c.is_suspended = false
+		AND (
+			c.buried_until IS NULL
+			OR c.buried_until <= $19
+		)
+		AND EXISTS (
+			SELECT 1
+			FROM filtered_word_readings fwr
+			WHERE fwr.word_id = w.id
+		)
+	ORDER BY c.due
+	LIMIT $18
+),
+new_words AS (
+	SELECT w.id,
+		w.word,
+		w.meanings,
+		NULL AS due
+	FROM word w
+	WHERE EXISTS (
+			SELECT 1
+			FROM filtered_word_readings fwr
+			WHERE fwr.word_id = w.id
+		)
+		AND NOT EXISTS (
+			SELECT 1
+			FROM card_fsrs c
+			WHERE c.user_id = $17
+				AND c.dictionary_id = $3
+				AND c.word = w.word
+		)
+	ORDER BY RANDOM()
+	LIMIT MAX(
+			0,
+			MIN(
+				$20,
+				$18 - (
+					SELECT COUNT(*)
+					FROM due_words
+				)
+			)
+		)
+),
+selected_words AS (
+	SELECT id,
+		word,
+		meanings,
+		due
+	FROM due_words
+	UNION ALL
+	SELECT id,
+		word,
+		meanings,
+		due
+	FROM new_words
+)
+SELECT GROUP_CONCAT(fwr.word_reading) AS "word_readings!: String",
+	GROUP_CONCAT(fwr.id) AS "word_reading_ids!: String",
+	sw.id AS "id!",
+	sw.word AS "word!",
+	sw.meanings AS "meanings!"
+FROM selected_words sw
+	JOIN filtered_word_readings fwr ON sw.id = fwr.word_id
+GROUP BY sw.id,
+	sw.word
+ORDER BY MIN(sw.due) IS NULL,
+	MIN(sw.due)
\ No newline at end of file
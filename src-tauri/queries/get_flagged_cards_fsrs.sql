@@ -0,0 +1,9 @@
+SELECT word
+FROM card_fsrs
+WHERE user_id = $1
+	AND dictionary_id = $2
+	AND (
+		is_suspended
+		OR buried_until IS NOT NULL
+		OR note IS NOT NULL
+	);
\ No newline at end of file
@@ -0,0 +1,5 @@
+SELECT easy_threshold,
+	hard_threshold,
+	parameters
+FROM fsrs_settings
+WHERE user_id = $1;
@@ -9,7 +9,11 @@ SELECT game_stats.id,
 	dictionary.name as "dictionary!",
 	timestamp,
 	real_rounds_count,
-	users_count
+	users_count,
+	word_filter,
+	seed,
+	word_sampling,
+	is_review_mode
 FROM game_stats
 LEFT JOIN font
 ON game_stats.font_id = font.id
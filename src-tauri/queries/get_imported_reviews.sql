@@ -0,0 +1,11 @@
+SELECT word,
+	rating,
+	reviewed_at AS "reviewed_at: NaiveDateTime"
+FROM review_log
+WHERE user_id = $1
+	AND dictionary_id = $2
+	AND answer_stats_id IS NULL
+	AND rating > 0
+ORDER BY word,
+	reviewed_at,
+	id;
\ No newline at end of file
@@ -0,0 +1,13 @@
+SELECT kanji,
+	jlpt,
+	grade,
+	stroke_count,
+	on_readings,
+	kun_readings,
+	meanings
+FROM kanji
+WHERE dictionary_id = $2
+	AND kanji IN (
+		SELECT value
+		FROM json_each($1)
+	)
\ No newline at end of file
@@ -0,0 +1,10 @@
+SELECT id,
+	is_common,
+	is_rare,
+	is_irregular,
+	pitch_accent
+FROM word_reading
+WHERE id IN (
+		SELECT value
+		FROM json_each($1)
+	)
\ No newline at end of file
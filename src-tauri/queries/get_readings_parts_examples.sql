@@ -0,0 +1,86 @@
+WITH source_readings AS (
+	SELECT wr.id AS reading_id,
+		wr.word_id
+	FROM json_each($1) ids
+		JOIN word_reading wr ON wr.id = ids.value
+),
+source_parts AS (
+	SELECT sr.reading_id,
+		sr.word_id,
+		wpr.id AS part_id,
+		wpr.word_part,
+		wpr.word_part_reading,
+		ROW_NUMBER() OVER (
+			PARTITION BY sr.reading_id
+			ORDER BY wrwpr.rowid
+		) AS part_order
+	FROM source_readings sr
+		JOIN word_reading_word_part_reading wrwpr ON sr.reading_id = wrwpr.word_reading_id
+		JOIN word_part_reading wpr ON wrwpr.word_part_reading_id = wpr.id
+	WHERE wpr.dictionary_id = $3
+),
+matching_words AS (
+	SELECT sp.reading_id,
+		sp.part_id,
+		sp.word_part,
+		sp.word_part_reading,
+		sp.part_order,
+		w.word,
+		w.frequency,
+		wr.word_reading,
+		ROW_NUMBER() OVER (
+			PARTITION BY sp.reading_id,
+			sp.part_id
+			ORDER BY CASE
+					WHEN w.frequency IS NULL THEN 1
+					ELSE 0
+				END,
+				w.frequency ASC
+		) AS rank
+	FROM source_parts sp
+		JOIN word_reading_word_part_reading wrwpr ON sp.part_id = wrwpr.word_part_reading_id
+		JOIN word_reading wr ON wrwpr.word_reading_id = wr.id
+		JOIN word w ON wr.word_id = w.id
+	WHERE w.id != sp.word_id
+		AND w.dictionary_id = $3
+),
+ranked_words AS (
+	SELECT reading_id,
+		part_id,
+		word_part,
+		word_part_reading,
+		part_order,
+		word,
+		frequency,
+		word_reading,
+		rank,
+		COUNT(*) OVER (PARTITION BY reading_id, part_id) as total_words,
+		SUM(
+			CASE
+				WHEN frequency IS NOT NULL THEN 1
+				ELSE 0
+			END
+		) OVER (PARTITION BY reading_id, part_id) as non_null_count
+	FROM matching_words
+)
+SELECT reading_id,
+	part_id,
+	word_part,
+	word_part_reading,
+	GROUP_CONCAT(word) AS top_words,
+	GROUP_CONCAT(COALESCE(frequency, 'NULL')) AS "top_words_frequencies: String",
+	GROUP_CONCAT(word_reading) AS top_words_readings
+FROM ranked_words
+WHERE rank <= $2
+	AND (
+		frequency IS NOT NULL
+		OR rank <= ($2 - non_null_count)
+		OR non_null_count = 0
+	)
+GROUP BY reading_id,
+	part_id,
+	word_part,
+	word_part_reading,
+	part_order
+ORDER BY reading_id,
+	part_order;
\ No newline at end of file
@@ -0,0 +1,14 @@
+SELECT answer_stats.id,
+	game_stats.dictionary_id,
+	answer_stats.word,
+	answer_stats.timestamp,
+	answer_stats.is_correct,
+	answer_stats.duration,
+	game_stats.round_duration
+FROM answer_stats
+	JOIN game_stats ON answer_stats.game_stats_id = game_stats.id
+WHERE answer_stats.user_id = $1
+ORDER BY game_stats.dictionary_id,
+	answer_stats.word,
+	answer_stats.timestamp,
+	answer_stats.id;
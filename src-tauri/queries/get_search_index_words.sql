@@ -0,0 +1,9 @@
+SELECT w.id AS "id!",
+	w.word AS "word!",
+	w.meanings AS "meanings!",
+	GROUP_CONCAT(wr.word_reading, ' ') AS "word_readings!: String"
+FROM word w
+	JOIN word_reading wr ON wr.word_id = w.id
+WHERE w.dictionary_id = $1
+GROUP BY w.id,
+	w.word
\ No newline at end of file
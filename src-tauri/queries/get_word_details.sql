@@ -0,0 +1,11 @@
+SELECT GROUP_CONCAT(wr.word_reading) AS "word_readings!: String",
+	GROUP_CONCAT(wr.id) AS "word_reading_ids!: String",
+	w.id AS "id!",
+	w.word AS "word!",
+	w.meanings AS "meanings!"
+FROM word w
+	JOIN word_reading wr ON wr.word_id = w.id
+WHERE w.word = $1
+	AND w.dictionary_id = $2
+GROUP BY w.id,
+	w.word
\ No newline at end of file
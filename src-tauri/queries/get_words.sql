@@ -1,27 +1,49 @@
-SELECT GROUP_CONCAT(wr.word_reading) AS "word_readings!: String",
-	GROUP_CONCAT(wr.id) AS "word_reading_ids!: String",
-	w.id AS "id!",
-	w.word AS "word!",
-	w.meanings AS "meanings"
-FROM (
-		SELECT id,
-			word,
-			meanings
-		FROM word
-		WHERE (
-				frequency IS NULL
-				OR frequency >= $2
-			)
-			AND (
-				$3 IS NULL
-				OR frequency <= $3
-			)
-			AND (
-				dictionary_id = $4
-			)
-		ORDER BY RANDOM()
-		LIMIT $1
-	) AS w
-	JOIN word_reading AS wr ON w.id = wr.word_id
-GROUP BY w.id,
-	w.word
\ No newline at end of file
+WITH selected_words AS (
+	SELECT w.id,
+		w.word,
+		w.meanings,
+		ids.key AS position
+	FROM json_each($1) ids
+		JOIN word w ON w.id = ids.value
+),
+filtered_word_readings AS (
+	SELECT wr.id,
+		wr.word_id,
+		wr.word_reading
+	FROM word_reading wr
+		JOIN selected_words sw ON sw.id = wr.word_id
+	WHERE (
+			$2 IS NULL
+			OR length(wr.word_reading) >= $2
+		)
+		AND (
+			$3 IS NULL
+			OR length(wr.word_reading) <= $3
+		)
+		AND NOT EXISTS (
+			SELECT 1
+			FROM json_each($4) wp
+			WHERE NOT EXISTS (
+					SELECT 1
+					FROM word_reading_word_part_reading wrwpr
+						JOIN word_part_reading wpr ON wpr.id = wrwpr.word_part_reading_id
+					WHERE wrwpr.word_reading_id = wr.id
+						AND wpr.word_part = json_extract(wp.value, '$.wordPart')
+						AND (
+							json_extract(wp.value, '$.wordPartReading') IS NULL
+							OR wpr.word_part_reading = json_extract(wp.value, '$.wordPartReading')
+						)
+				)
+		)
+)
+SELECT GROUP_CONCAT(fwr.word_reading) AS "word_readings!: String",
+	GROUP_CONCAT(fwr.id) AS "word_reading_ids!: String",
+	sw.id AS "id!",
+	sw.word AS "word!",
+	sw.meanings AS "meanings"
+FROM selected_words sw
+	JOIN filtered_word_readings fwr ON sw.id = fwr.word_id
+GROUP BY sw.id,
+	sw.word,
+	sw.position
+ORDER BY sw.position;
\ No newline at end of file
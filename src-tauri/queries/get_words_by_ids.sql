@@ -0,0 +1,11 @@
+SELECT GROUP_CONCAT(wr.word_reading) AS "word_readings!: String",
+	GROUP_CONCAT(wr.id) AS "word_reading_ids!: String",
+	w.id AS "id!",
+	w.word AS "word!",
+	w.meanings AS "meanings!"
+FROM json_each($1) ids
+	JOIN word w ON w.id = ids.value
+	JOIN word_reading wr ON wr.word_id = w.id
+GROUP BY w.id,
+	w.word
+ORDER BY MIN(ids.key)
\ No newline at end of file
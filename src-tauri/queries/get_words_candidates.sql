@@ -0,0 +1,168 @@
+WITH filtered_words AS (
+	SELECT id,
+		word,
+		frequency
+	FROM word
+	WHERE (
+			frequency IS NULL
+			OR frequency >= $1
+		)
+		AND (
+			$2 IS NULL
+			OR frequency <= $2
+		)
+		AND (
+			dictionary_id = $3
+		)
+		AND (
+			$8 IS NULL
+			OR length(word) >= $8
+		)
+		AND (
+			$9 IS NULL
+			OR length(word) <= $9
+		)
+		AND (
+			json_array_length($6) = 0
+			OR word IN (
+				SELECT value
+				FROM json_each($6)
+			)
+		)
+		AND word NOT IN (
+			SELECT value
+			FROM json_each($7)
+		)
+		AND NOT EXISTS (
+			SELECT 1
+			FROM json_each($5) ewp
+			WHERE instr(word.word, ewp.value) > 0
+		)
+		AND (
+			(
+				$12 IS NULL
+				AND $13 IS NULL
+			)
+			OR (
+				SELECT COUNT(*)
+				FROM word_reading wr
+				WHERE wr.word_id = word.id
+			) BETWEEN IFNULL($12, 0) AND IFNULL($13, 9223372036854775807)
+		)
+		AND (
+			$14 IS NULL
+			OR EXISTS (
+				SELECT 1
+				FROM word_reading wr
+					JOIN word_reading_tag wrt ON wrt.word_reading_id = wr.id
+				WHERE wr.word_id = word.id
+					AND wrt.tag IN ('ateji', 'iK', 'io', 'ik', 'gikun')
+			) = $14
+		)
+		AND (
+			(
+				$15 IS NULL
+				AND $16 IS NULL
+			)
+			OR NOT EXISTS (
+				SELECT 1
+				FROM word_kanji wk
+					LEFT JOIN kanji k ON k.kanji = wk.kanji
+					AND k.dictionary_id = word.dictionary_id
+				WHERE wk.word_id = word.id
+					AND (
+						(
+							$15 IS NOT NULL
+							AND (
+								k.jlpt IS NULL
+								OR k.jlpt < $15
+							)
+						)
+						OR (
+							$16 IS NOT NULL
+							AND (
+								k.grade IS NULL
+								OR k.grade > $16
+							)
+						)
+					)
+			)
+		)
+),
+filtered_word_readings AS (
+	SELECT wr.id,
+		wr.word_id,
+		wr.word_reading
+	FROM word_reading wr
+		JOIN filtered_words fw ON fw.id = wr.word_id
+	WHERE (
+			$10 IS NULL
+			OR length(wr.word_reading) >= $10
+		)
+		AND (
+			$11 IS NULL
+			OR length(wr.word_reading) <= $11
+		)
+		AND NOT EXISTS (
+			SELECT 1
+			FROM json_each($4) wp
+			WHERE NOT EXISTS (
+					SELECT 1
+					FROM word_reading_word_part_reading wrwpr
+						JOIN word_part_reading wpr ON wpr.id = wrwpr.word_part_reading_id
+					WHERE wrwpr.word_reading_id = wr.id
+						AND wpr.word_part = json_extract(wp.value, '$.wordPart')
+						AND (
+							json_extract(wp.value, '$.wordPartReading') IS NULL
+							OR wpr.word_part_reading = json_extract(wp.value, '$.wordPartReading')
+						)
+				)
+		)
+),
+missed_words AS (
+	SELECT ans.word,
+		COUNT(*) AS answers_count,
+		SUM(
+			CASE
+				WHEN ans.is_correct THEN 0
+				ELSE 1
+			END
+		) AS wrong_count
+	FROM answer_stats ans
+		JOIN game_stats gs ON gs.id = ans.game_stats_id
+	WHERE ans.user_id = $17
+		AND gs.dictionary_id = $3
+	GROUP BY ans.word
+),
+reading_counts AS (
+	SELECT word_id,
+		COUNT(*) AS readings_count
+	FROM filtered_word_readings
+	GROUP BY word_id
+)
+SELECT json_group_array(
+		json_array(
+			id,
+			word,
+			frequency,
+			readings_count,
+			answers_count,
+			wrong_count
+		)
+	) AS "candidates!: String"
+FROM (
+		SELECT fw.id,
+			fw.word,
+			fw.frequency,
+			rc.readings_count,
+			IFNULL(mw.answers_count, 0) AS answers_count,
+			IFNULL(mw.wrong_count, 0) AS wrong_count
+		FROM filtered_words fw
+			JOIN reading_counts rc ON rc.word_id = fw.id
+			LEFT JOIN missed_words mw ON mw.word = fw.word
+		ORDER BY CASE
+				WHEN $18 IS NULL THEN 0
+				ELSE RANDOM()
+			END
+		LIMIT IFNULL($18, -1)
+	);
\ No newline at end of file
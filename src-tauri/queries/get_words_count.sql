@@ -1,5 +1,7 @@
 WITH filtered_words AS (
-	SELECT id
+	SELECT id,
+		word,
+		meanings
 	FROM word
 	WHERE (
 			frequency IS NULL
@@ -10,31 +12,112 @@ WITH filtered_words AS (
 			OR frequency <= $2
 		)
 		AND (
-			dictionary_id = $5
+			dictionary_id = $3
 		)
-),
-filtered_word_parts AS (
-	SELECT id
-	FROM word_part_reading
-	WHERE (
-			$3 IS NULL
-			OR word_part = $3
+		AND (
+			$8 IS NULL
+			OR length(word) >= $8
+		)
+		AND (
+			$9 IS NULL
+			OR length(word) <= $9
+		)
+		AND (
+			json_array_length($6) = 0
+			OR word IN (
+				SELECT value
+				FROM json_each($6)
+			)
+		)
+		AND word NOT IN (
+			SELECT value
+			FROM json_each($7)
+		)
+		AND NOT EXISTS (
+			SELECT 1
+			FROM json_each($5) ewp
+			WHERE instr(word.word, ewp.value) > 0
 		)
 		AND (
-			$4 IS NULL
-			OR word_part_reading = $4
+			(
+				$12 IS NULL
+				AND $13 IS NULL
+			)
+			OR (
+				SELECT COUNT(*)
+				FROM word_reading wr
+				WHERE wr.word_id = word.id
+			) BETWEEN IFNULL($12, 0) AND IFNULL($13, 9223372036854775807)
+		)
+		AND (
+			$14 IS NULL
+			OR EXISTS (
+				SELECT 1
+				FROM word_reading wr
+					JOIN word_reading_tag wrt ON wrt.word_reading_id = wr.id
+				WHERE wr.word_id = word.id
+					AND wrt.tag IN ('ateji', 'iK', 'io', 'ik', 'gikun')
+			) = $14
+		)
+		AND (
+			(
+				$15 IS NULL
+				AND $16 IS NULL
+			)
+			OR NOT EXISTS (
+				SELECT 1
+				FROM word_kanji wk
+					LEFT JOIN kanji k ON k.kanji = wk.kanji
+					AND k.dictionary_id = word.dictionary_id
+				WHERE wk.word_id = word.id
+					AND (
+						(
+							$15 IS NOT NULL
+							AND (
+								k.jlpt IS NULL
+								OR k.jlpt < $15
+							)
+						)
+						OR (
+							$16 IS NOT NULL
+							AND (
+								k.grade IS NULL
+								OR k.grade > $16
+							)
+						)
+					)
+			)
 		)
 ),
 filtered_word_readings AS (
-	SELECT DISTINCT wr.word_id
+	SELECT wr.id,
+		wr.word_id,
+		wr.word_reading
 	FROM word_reading wr
-		LEFT JOIN word_reading_word_part_reading wrwpr ON wr.id = wrwpr.word_reading_id
-	WHERE $3 IS NULL
-		OR wrwpr.word_part_reading_id IN (
-			SELECT id
-			FROM filtered_word_parts
+		JOIN filtered_words fw ON fw.id = wr.word_id
+	WHERE (
+			$10 IS NULL
+			OR length(wr.word_reading) >= $10
+		)
+		AND (
+			$11 IS NULL
+			OR length(wr.word_reading) <= $11
+		)
+		AND NOT EXISTS (
+			SELECT 1
+			FROM json_each($4) wp
+			WHERE NOT EXISTS (
+					SELECT 1
+					FROM word_reading_word_part_reading wrwpr
+						JOIN word_part_reading wpr ON wpr.id = wrwpr.word_part_reading_id
+					WHERE wrwpr.word_reading_id = wr.id
+						AND wpr.word_part = json_extract(wp.value, '$.wordPart')
+						AND (
+							json_extract(wp.value, '$.wordPartReading') IS NULL
+							OR wpr.word_part_reading = json_extract(wp.value, '$.wordPartReading')
+						)
+				)
 		)
 )
-SELECT COUNT(*) AS count
-FROM filtered_words fw
-	JOIN filtered_word_readings fwr ON fw.id = fwr.word_id;
\ No newline at end of file
+SELECT COUNT(DISTINCT word_id) AS count
+FROM filtered_word_readings;
\ No newline at end of file
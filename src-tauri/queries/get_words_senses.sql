@@ -0,0 +1,12 @@
+SELECT word_id,
+	entry_index,
+	sense_index,
+	pos,
+	misc,
+	field,
+	priority
+FROM word_sense
+WHERE word_id IN (
+		SELECT value
+		FROM json_each($1)
+	)
\ No newline at end of file
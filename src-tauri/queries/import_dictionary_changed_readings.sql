@@ -0,0 +1,49 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+SELECT mwr.id
+FROM main.word_reading mwr
+	JOIN main.word mw ON mw.id = mwr.word_id
+	JOIN dict_db.word dw ON dw.word = mw.word
+	JOIN dict_db.word_reading dwr ON dwr.word_id = dw.id
+	AND dwr.word_reading = mwr.word_reading
+WHERE mw.dictionary_id = (
+		SELECT id
+		FROM dict_id
+	)
+	AND (
+		EXISTS (
+			SELECT 1
+			FROM main.word_reading_word_part_reading mwrwpr
+				JOIN main.word_part_reading mwpr ON mwpr.id = mwrwpr.word_part_reading_id
+			WHERE mwrwpr.word_reading_id = mwr.id
+				AND NOT EXISTS (
+					SELECT 1
+					FROM dict_db.word_reading_word_part_reading dwrwpr
+						JOIN dict_db.word_part_reading dwpr ON dwpr.id = dwrwpr.word_part_reading_id
+					WHERE dwrwpr.word_reading_id = dwr.id
+						AND dwpr.word_part = mwpr.word_part
+						AND dwpr.word_part_reading = mwpr.word_part_reading
+				)
+		)
+		OR EXISTS (
+			SELECT 1
+			FROM dict_db.word_reading_word_part_reading dwrwpr
+				JOIN dict_db.word_part_reading dwpr ON dwpr.id = dwrwpr.word_part_reading_id
+			WHERE dwrwpr.word_reading_id = dwr.id
+				AND NOT EXISTS (
+					SELECT 1
+					FROM main.word_reading_word_part_reading mwrwpr
+						JOIN main.word_part_reading mwpr ON mwpr.id = mwrwpr.word_part_reading_id
+					WHERE mwrwpr.word_reading_id = mwr.id
+						AND mwpr.word_part = dwpr.word_part
+						AND mwpr.word_part_reading = dwpr.word_part_reading
+				)
+		)
+	);
\ No newline at end of file
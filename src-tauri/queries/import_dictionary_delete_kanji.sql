@@ -0,0 +1,14 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+DELETE FROM main.kanji
+WHERE dictionary_id = (
+		SELECT id
+		FROM dict_id
+	);
\ No newline at end of file
@@ -0,0 +1,32 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+DELETE FROM main.word_reading_word_part_reading
+WHERE rowid IN (
+		SELECT mwrwpr.rowid
+		FROM main.word_reading_word_part_reading mwrwpr
+			JOIN main.word_reading mwr ON mwr.id = mwrwpr.word_reading_id
+			JOIN main.word mw ON mw.id = mwr.word_id
+			JOIN main.word_part_reading mwpr ON mwpr.id = mwrwpr.word_part_reading_id
+		WHERE mw.dictionary_id = (
+				SELECT id
+				FROM dict_id
+			)
+			AND NOT EXISTS (
+				SELECT 1
+				FROM dict_db.word_reading_word_part_reading dwrwpr
+					JOIN dict_db.word_reading dwr ON dwr.id = dwrwpr.word_reading_id
+					JOIN dict_db.word dw ON dw.id = dwr.word_id
+					JOIN dict_db.word_part_reading dwpr ON dwpr.id = dwrwpr.word_part_reading_id
+				WHERE dw.word = mw.word
+					AND dwr.word_reading = mwr.word_reading
+					AND dwpr.word_part = mwpr.word_part
+					AND dwpr.word_part_reading = mwpr.word_part_reading
+			)
+	);
\ No newline at end of file
@@ -0,0 +1,20 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+DELETE FROM main.word_part_reading
+WHERE dictionary_id = (
+		SELECT id
+		FROM dict_id
+	)
+	AND NOT EXISTS (
+		SELECT 1
+		FROM dict_db.word_part_reading dwpr
+		WHERE dwpr.word_part = main.word_part_reading.word_part
+			AND dwpr.word_part_reading = main.word_part_reading.word_part_reading
+	);
\ No newline at end of file
@@ -0,0 +1,19 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+DELETE FROM main.word_reading_tag
+WHERE word_reading_id IN (
+		SELECT mwr.id
+		FROM main.word_reading mwr
+			JOIN main.word mw ON mw.id = mwr.word_id
+		WHERE mw.dictionary_id = (
+				SELECT id
+				FROM dict_id
+			)
+	);
\ No newline at end of file
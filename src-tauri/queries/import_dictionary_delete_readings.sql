@@ -0,0 +1,26 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+DELETE FROM main.word_reading
+WHERE id IN (
+		SELECT mwr.id
+		FROM main.word_reading mwr
+			JOIN main.word mw ON mw.id = mwr.word_id
+		WHERE mw.dictionary_id = (
+				SELECT id
+				FROM dict_id
+			)
+			AND NOT EXISTS (
+				SELECT 1
+				FROM dict_db.word_reading dwr
+					JOIN dict_db.word dw ON dw.id = dwr.word_id
+				WHERE dw.word = mw.word
+					AND dwr.word_reading = mwr.word_reading
+			)
+	);
\ No newline at end of file
@@ -0,0 +1,25 @@
+INSERT INTO main.dictionary (
+		guid,
+		name,
+		is_exist,
+		stats_config,
+		description,
+		version,
+		author,
+		license,
+		source_url,
+		created_at,
+		min_schema_version
+	)
+VALUES ($1, $2, true, $3, $4, $5, $6, $7, $8, $9, $10)
+ON CONFLICT(guid) DO UPDATE SET
+	name = excluded.name,
+	is_exist = true,
+	stats_config = excluded.stats_config,
+	description = excluded.description,
+	version = excluded.version,
+	author = excluded.author,
+	license = excluded.license,
+	source_url = excluded.source_url,
+	created_at = excluded.created_at,
+	min_schema_version = excluded.min_schema_version;
\ No newline at end of file
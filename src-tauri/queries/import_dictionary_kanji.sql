@@ -0,0 +1,34 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+INSERT INTO main.kanji (
+		kanji,
+		jlpt,
+		grade,
+		stroke_count,
+		on_readings,
+		kun_readings,
+		meanings,
+		dictionary_id
+	)
+SELECT k.kanji,
+	k.jlpt,
+	k.grade,
+	k.stroke_count,
+	k.on_readings,
+	k.kun_readings,
+	k.meanings,
+	(
+		SELECT id
+		FROM dict_id
+	)
+FROM dict_db.kanji k
+WHERE k.rowid > $1
+	AND k.rowid <= $2
+ON CONFLICT DO NOTHING;
\ No newline at end of file
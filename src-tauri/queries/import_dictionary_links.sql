@@ -0,0 +1,38 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+INSERT INTO main.word_reading_word_part_reading (word_reading_id, word_part_reading_id)
+SELECT (
+		SELECT mwr.id
+		FROM main.word_reading mwr
+			JOIN main.word mw ON mw.id = mwr.word_id
+			JOIN dict_db.word dw ON dw.word = mw.word
+			JOIN dict_db.word_reading dwr ON dwr.word_id = dw.id
+			AND dwr.word_reading = mwr.word_reading
+		WHERE mw.dictionary_id = (
+				SELECT id
+				FROM dict_id
+			)
+			AND dwr.id = dwrwpr.word_reading_id
+	),
+	(
+		SELECT mwpr.id
+		FROM main.word_part_reading mwpr
+			JOIN dict_db.word_part_reading dwpr ON dwpr.word_part = mwpr.word_part
+			AND dwpr.word_part_reading = mwpr.word_part_reading
+		WHERE mwpr.dictionary_id = (
+				SELECT id
+				FROM dict_id
+			)
+			AND dwpr.id = dwrwpr.word_part_reading_id
+	)
+FROM dict_db.word_reading_word_part_reading dwrwpr
+WHERE dwrwpr.rowid > $1
+	AND dwrwpr.rowid <= $2
+ON CONFLICT DO NOTHING;
\ No newline at end of file
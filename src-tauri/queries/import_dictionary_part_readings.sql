@@ -0,0 +1,20 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+INSERT INTO main.word_part_reading (word_part, word_part_reading, dictionary_id)
+SELECT wpr.word_part,
+	wpr.word_part_reading,
+	(
+		SELECT id
+		FROM dict_id
+	)
+FROM dict_db.word_part_reading wpr
+WHERE wpr.rowid > $1
+	AND wpr.rowid <= $2
+ON CONFLICT DO NOTHING;
\ No newline at end of file
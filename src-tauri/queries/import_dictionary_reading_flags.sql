@@ -0,0 +1,29 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+UPDATE main.word_reading AS mwr
+SET is_common = dwr.is_common,
+	is_rare = dwr.is_rare,
+	is_irregular = dwr.is_irregular,
+	pitch_accent = dwr.pitch_accent
+FROM main.word mw
+	JOIN dict_db.word dw ON dw.word = mw.word
+	JOIN dict_db.word_reading dwr ON dwr.word_id = dw.id
+WHERE mw.id = mwr.word_id
+	AND mw.dictionary_id = (
+		SELECT id
+		FROM dict_id
+	)
+	AND dwr.word_reading = mwr.word_reading
+	AND (
+		mwr.is_common IS NOT dwr.is_common
+		OR mwr.is_rare IS NOT dwr.is_rare
+		OR mwr.is_irregular IS NOT dwr.is_irregular
+		OR mwr.pitch_accent IS NOT dwr.pitch_accent
+	);
\ No newline at end of file
@@ -0,0 +1,28 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+INSERT INTO main.word_reading_tag (word_reading_id, tag)
+SELECT (
+		SELECT mwr.id
+		FROM main.word_reading mwr
+			JOIN main.word mw ON mw.id = mwr.word_id
+			JOIN dict_db.word dw ON dw.word = mw.word
+			JOIN dict_db.word_reading dwr ON dwr.word_id = dw.id
+			AND dwr.word_reading = mwr.word_reading
+		WHERE mw.dictionary_id = (
+				SELECT id
+				FROM dict_id
+			)
+			AND dwr.id = dwrt.word_reading_id
+	),
+	dwrt.tag
+FROM dict_db.word_reading_tag dwrt
+WHERE dwrt.rowid > $1
+	AND dwrt.rowid <= $2
+ON CONFLICT DO NOTHING;
\ No newline at end of file
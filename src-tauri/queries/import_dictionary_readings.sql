@@ -0,0 +1,25 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+INSERT INTO main.word_reading (word_id, word_reading)
+SELECT (
+		SELECT mw.id
+		FROM main.word mw
+		WHERE mw.word = dw.word
+			AND mw.dictionary_id = (
+				SELECT id
+				FROM dict_id
+			)
+	),
+	dwr.word_reading
+FROM dict_db.word_reading dwr
+	JOIN dict_db.word dw ON dw.id = dwr.word_id
+WHERE dwr.rowid > $1
+	AND dwr.rowid <= $2
+ON CONFLICT DO NOTHING;
\ No newline at end of file
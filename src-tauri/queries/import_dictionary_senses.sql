@@ -0,0 +1,38 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+INSERT INTO main.word_sense (
+		word_id,
+		entry_index,
+		sense_index,
+		pos,
+		misc,
+		field,
+		priority
+	)
+SELECT (
+		SELECT mw.id
+		FROM main.word mw
+		WHERE mw.word = dw.word
+			AND mw.dictionary_id = (
+				SELECT id
+				FROM dict_id
+			)
+	),
+	dws.entry_index,
+	dws.sense_index,
+	dws.pos,
+	dws.misc,
+	dws.field,
+	dws.priority
+FROM dict_db.word_sense dws
+	JOIN dict_db.word dw ON dw.id = dws.word_id
+WHERE dws.rowid > $1
+	AND dws.rowid <= $2
+ON CONFLICT DO NOTHING;
\ No newline at end of file
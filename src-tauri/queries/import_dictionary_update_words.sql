@@ -0,0 +1,22 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+UPDATE main.word AS mw
+SET frequency = dw.frequency,
+	meanings = dw.meanings
+FROM dict_db.word dw
+WHERE dw.word = mw.word
+	AND mw.dictionary_id = (
+		SELECT id
+		FROM dict_id
+	)
+	AND (
+		dw.frequency IS NOT mw.frequency
+		OR dw.meanings IS NOT mw.meanings
+	);
\ No newline at end of file
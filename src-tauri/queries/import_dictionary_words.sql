@@ -0,0 +1,21 @@
+WITH dict_id AS (
+	SELECT id
+	FROM main.dictionary
+	WHERE guid = (
+			SELECT guid
+			FROM dict_db.dictionary_info
+			LIMIT 1
+		)
+)
+INSERT INTO main.word (word, frequency, dictionary_id, meanings)
+SELECT w.word,
+	w.frequency,
+	(
+		SELECT id
+		FROM dict_id
+	),
+	w.meanings
+FROM dict_db.word w
+WHERE w.rowid > $1
+	AND w.rowid <= $2
+ON CONFLICT DO NOTHING;
\ No newline at end of file
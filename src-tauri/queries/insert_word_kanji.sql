@@ -0,0 +1,21 @@
+WITH RECURSIVE word_chars(word_id, kanji, rest) AS (
+	SELECT id,
+		'',
+		word
+	FROM word
+	WHERE dictionary_id = $1
+	UNION ALL
+	SELECT word_id,
+		substr(rest, 1, 1),
+		substr(rest, 2)
+	FROM word_chars
+	WHERE rest <> ''
+)
+INSERT OR IGNORE INTO word_kanji (word_id, kanji)
+SELECT word_id,
+	kanji
+FROM word_chars
+WHERE unicode(kanji) BETWEEN 13312 AND 19903
+	OR unicode(kanji) BETWEEN 19968 AND 40959
+	OR unicode(kanji) BETWEEN 63744 AND 64255
+	OR unicode(kanji) BETWEEN 131072 AND 201551
\ No newline at end of file
@@ -0,0 +1,68 @@
+DELETE FROM review_log
+WHERE dictionary_id = ?;
+
+DELETE FROM card_fsrs
+WHERE dictionary_id = ?;
+
+DELETE FROM answer_stats
+WHERE game_stats_id IN (
+        SELECT id
+        FROM game_stats
+        WHERE dictionary_id = ?
+    );
+
+DELETE FROM game_stats
+WHERE dictionary_id = ?;
+
+DELETE FROM word_reading_word_part_reading
+WHERE word_reading_id IN (
+        SELECT wr.id
+        FROM word_reading wr
+            JOIN word w ON w.id = wr.word_id
+        WHERE w.dictionary_id = ?
+    );
+
+DELETE FROM word_part_reading
+WHERE dictionary_id = ?;
+
+DELETE FROM word_reading_tag
+WHERE word_reading_id IN (
+        SELECT wr.id
+        FROM word_reading wr
+            JOIN word w ON w.id = wr.word_id
+        WHERE w.dictionary_id = ?
+    );
+
+DELETE FROM word_reading
+WHERE word_id IN (
+        SELECT id
+        FROM word
+        WHERE dictionary_id = ?
+    );
+
+DELETE FROM word_sense
+WHERE word_id IN (
+        SELECT id
+        FROM word
+        WHERE dictionary_id = ?
+    );
+
+DELETE FROM word_kanji
+WHERE word_id IN (
+        SELECT id
+        FROM word
+        WHERE dictionary_id = ?
+    );
+
+DELETE FROM kanji
+WHERE dictionary_id = ?;
+
+DELETE FROM word_search
+WHERE dictionary_id = ?;
+
+DELETE FROM word
+WHERE dictionary_id = ?;
+
+DELETE FROM dictionary
+WHERE id = ?
+    AND NOT is_exist;
\ No newline at end of file
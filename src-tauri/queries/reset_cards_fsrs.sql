@@ -0,0 +1,26 @@
+DELETE FROM review_log
+WHERE user_id = $1
+	AND dictionary_id = $2;
+DELETE FROM card_fsrs
+WHERE user_id = $3
+	AND dictionary_id = $4
+	AND is_suspended = false
+	AND buried_until IS NULL
+	AND note IS NULL;
+UPDATE card_fsrs
+SET due = $5,
+	stability = 0,
+	difficulty = 0,
+	elapsed_days = 0,
+	scheduled_days = 0,
+	reps = 0,
+	lapses = 0,
+	state = 0,
+	last_review = $5,
+	updated_at = CURRENT_TIMESTAMP
+WHERE user_id = $6
+	AND dictionary_id = $7
+	AND (
+		reps > 0
+		OR state != 0
+	);
@@ -0,0 +1,5 @@
+INSERT INTO fsrs_settings (user_id, easy_threshold, hard_threshold, parameters)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT(user_id) DO UPDATE SET
+	parameters = excluded.parameters,
+	updated_at = CURRENT_TIMESTAMP;
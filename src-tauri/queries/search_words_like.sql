@@ -0,0 +1,14 @@
+SELECT w.id
+FROM word_search ws
+	JOIN word w ON w.id = ws.rowid
+WHERE ws.dictionary_id = $1
+	AND (
+		ws.word LIKE $2 ESCAPE '\'
+		OR ws.meanings LIKE $2 ESCAPE '\'
+		OR ws.readings LIKE $3 ESCAPE '\'
+		OR ws.romaji LIKE $4 ESCAPE '\'
+	)
+ORDER BY w.word = $5 DESC,
+	w.frequency IS NULL,
+	w.frequency,
+	w.id
\ No newline at end of file
@@ -0,0 +1,9 @@
+SELECT w.id
+FROM word_search ws
+	JOIN word w ON w.id = ws.rowid
+WHERE word_search MATCH $1
+	AND ws.dictionary_id = $2
+ORDER BY w.word = $3 DESC,
+	w.frequency IS NULL,
+	w.frequency,
+	w.id
\ No newline at end of file
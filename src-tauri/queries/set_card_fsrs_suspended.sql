@@ -0,0 +1,6 @@
+UPDATE card_fsrs
+SET is_suspended = $1,
+	updated_at = CURRENT_TIMESTAMP
+WHERE user_id = $2
+	AND dictionary_id = $3
+	AND word = $4;
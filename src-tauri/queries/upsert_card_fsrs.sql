@@ -1,8 +1,8 @@
 INSERT INTO card_fsrs (
-    word, due, stability, difficulty, elapsed_days,
+    user_id, dictionary_id, word, due, stability, difficulty, elapsed_days,
     scheduled_days, reps, lapses, state, last_review
-) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-ON CONFLICT(word) DO UPDATE SET
+) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(user_id, dictionary_id, word) DO UPDATE SET
     due = excluded.due,
     stability = excluded.stability,
     difficulty = excluded.difficulty,
@@ -0,0 +1,6 @@
+INSERT INTO fsrs_settings (user_id, easy_threshold, hard_threshold)
+VALUES ($1, $2, $3)
+ON CONFLICT(user_id) DO UPDATE SET
+	easy_threshold = excluded.easy_threshold,
+	hard_threshold = excluded.hard_threshold,
+	updated_at = CURRENT_TIMESTAMP;
@@ -0,0 +1,84 @@
+SELECT (
+		SELECT COUNT(*)
+		FROM dictionary_info
+	) AS dictionary_info_count,
+	(
+		SELECT COUNT(*)
+		FROM dictionary_info
+		WHERE TRIM(COALESCE(guid, '')) = ''
+			OR TRIM(COALESCE(name, '')) = ''
+	) AS empty_dictionary_info_count,
+	(
+		SELECT COUNT(*)
+		FROM word
+	) AS words_count,
+	(
+		SELECT COUNT(*)
+		FROM (
+				SELECT word
+				FROM word
+				GROUP BY word
+				HAVING COUNT(*) > 1
+			)
+	) AS duplicate_words_count,
+	(
+		SELECT COUNT(*)
+		FROM word
+		WHERE TRIM(COALESCE(meanings, '')) = ''
+	) AS empty_meanings_count,
+	(
+		SELECT COUNT(*)
+		FROM word w
+		WHERE NOT EXISTS (
+				SELECT 1
+				FROM word_reading wr
+				WHERE wr.word_id = w.id
+			)
+	) AS words_without_readings_count,
+	(
+		SELECT COUNT(*)
+		FROM word_reading wr
+			LEFT JOIN word w ON w.id = wr.word_id
+		WHERE w.id IS NULL
+	) AS dangling_readings_count,
+	(
+		SELECT COUNT(*)
+		FROM (
+				SELECT word_id
+				FROM word_reading
+				GROUP BY word_id,
+					word_reading
+				HAVING COUNT(*) > 1
+			)
+	) AS duplicate_readings_count,
+	(
+		SELECT COUNT(*)
+		FROM (
+				SELECT word_part
+				FROM word_part_reading
+				GROUP BY word_part,
+					word_part_reading
+				HAVING COUNT(*) > 1
+			)
+	) AS duplicate_part_readings_count,
+	(
+		SELECT COUNT(*)
+		FROM word_part_reading wpr
+		WHERE NOT EXISTS (
+				SELECT 1
+				FROM word_reading_word_part_reading wrwpr
+				WHERE wrwpr.word_part_reading_id = wpr.id
+			)
+	) AS unused_part_readings_count,
+	(
+		SELECT COUNT(*)
+		FROM word_reading_word_part_reading wrwpr
+			LEFT JOIN word_reading wr ON wr.id = wrwpr.word_reading_id
+		WHERE wr.id IS NULL
+	) AS dangling_link_readings_count,
+	(
+		SELECT COUNT(*)
+		FROM word_reading_word_part_reading wrwpr
+			LEFT JOIN word_part_reading wpr ON wpr.id = wrwpr.word_part_reading_id
+		WHERE wpr.id IS NULL
+	) AS dangling_link_parts_count;
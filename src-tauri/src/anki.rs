@@ -0,0 +1,415 @@
+use chrono::DateTime;
+use indexmap::IndexMap;
+use regex::Regex;
+use rs_fsrs::Rating;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use tauri::{AppHandle, Runtime};
+use uuid::Uuid;
+
+use crate::db::{DictionaryImportSummary, get_dictionary_guid, get_user_id, seed_cards_fsrs};
+use crate::dict_builder::{
+    DictionaryBuildReport, DictionaryData, WordListRecord, add_word_list_record,
+    import_dictionary_data,
+};
+use crate::fsrs::Review;
+
+const FIELD_SEPARATOR: char = '\x1f';
+
+// Newer exports keep a compressed collection next to a stub for old clients
+const COLLECTION_FILES: [&str; 3] = [
+    "collection.anki21b",
+    "collection.anki21",
+    "collection.anki2",
+];
+
+static BREAK_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<br\s*/?>|</div>|</p>|</li>").unwrap());
+static TAG_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+static SOUND_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[sound:[^\]]*\]").unwrap());
+static FURIGANA_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[[^\]]*\]").unwrap());
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnkiNoteType {
+    id: i64,
+    name: String,
+    fields: Vec<String>,
+    notes_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnkiImportReport {
+    dictionary: DictionaryBuildReport,
+    summary: DictionaryImportSummary,
+    cards_count: u64,
+    reviews_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyNoteType {
+    name: String,
+    flds: Vec<LegacyField>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyField {
+    name: String,
+    ord: i64,
+}
+
+// The extracted collection is removed once dropped
+struct AnkiCollection {
+    path: PathBuf,
+}
+
+impl Drop for AnkiCollection {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn extract_collection(apkg_path: &str) -> Result<AnkiCollection, String> {
+    let file = std::fs::File::open(apkg_path).map_err(|e| format!("Failed to open deck: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read deck archive: {}", e))?;
+
+    let name = COLLECTION_FILES
+        .into_iter()
+        .find(|name| archive.index_for_name(name).is_some())
+        .ok_or_else(|| "Deck archive has no collection".to_string())?;
+
+    let mut bytes = Vec::new();
+    archive
+        .by_name(name)
+        .map_err(|e| format!("Failed to read collection: {}", e))?
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read collection: {}", e))?;
+
+    if name == "collection.anki21b" {
+        bytes = zstd::stream::decode_all(bytes.as_slice())
+            .map_err(|e| format!("Failed to decompress collection: {}", e))?;
+    }
+
+    let collection = AnkiCollection {
+        path: std::env::temp_dir().join(format!("kanjilab_anki_{}.db", Uuid::new_v4())),
+    };
+    std::fs::write(&collection.path, bytes)
+        .map_err(|e| format!("Failed to extract collection: {}", e))?;
+
+    Ok(collection)
+}
+
+async fn open_collection(apkg_path: &str) -> Result<(AnkiCollection, SqliteConnection), String> {
+    let path = apkg_path.to_string();
+    let collection = tokio::task::spawn_blocking(move || extract_collection(&path))
+        .await
+        .map_err(|e| format!("Failed to extract collection: {}", e))??;
+
+    let options = SqliteConnectOptions::new()
+        .filename(&collection.path)
+        .read_only(true);
+
+    let conn = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("Failed to open collection: {}", e))?;
+
+    Ok((collection, conn))
+}
+
+// Anki fields are HTML, line breaks separate meanings
+fn clean_field(value: &str) -> String {
+    let value = BREAK_PATTERN.replace_all(value, ";");
+    let value = TAG_PATTERN.replace_all(&value, "");
+    let value = SOUND_PATTERN.replace_all(&value, "");
+
+    value
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+// Word and reading fields often hold furigana like 日本[にほん]
+fn note_record(word: String, reading: String, meanings: Option<String>) -> WordListRecord {
+    let mut furigana = None;
+
+    let word = if word.contains('[') {
+        let plain: String = FURIGANA_PATTERN
+            .replace_all(&word, "")
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        furigana = Some(word);
+        plain
+    } else {
+        word
+    };
+
+    let reading = if reading.contains('[') {
+        furigana = Some(reading);
+        String::new()
+    } else {
+        reading
+    };
+
+    WordListRecord {
+        word,
+        reading,
+        frequency: None,
+        meanings,
+        furigana,
+    }
+}
+
+async fn load_note_types(conn: &mut SqliteConnection) -> Result<Vec<AnkiNoteType>, String> {
+    let has_notetypes: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'notetypes'",
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to read collection: {}", e))?;
+
+    let mut note_types: Vec<AnkiNoteType> = Vec::new();
+
+    if has_notetypes {
+        let rows: Vec<(i64, String, String)> =
+            sqlx::query_as(include_str!("../queries/anki_get_note_type_fields.sql"))
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to read note types: {}", e))?;
+
+        for (id, name, field) in rows {
+            match note_types.last_mut() {
+                Some(note_type) if note_type.id == id => note_type.fields.push(field),
+                _ => note_types.push(AnkiNoteType {
+                    id,
+                    name,
+                    fields: vec![field],
+                    notes_count: 0,
+                }),
+            }
+        }
+    } else {
+        // Older collections keep note types as JSON in the col table
+        let models: String = sqlx::query_scalar("SELECT models FROM col")
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to read note types: {}", e))?;
+        let models: HashMap<String, LegacyNoteType> = serde_json::from_str(&models)
+            .map_err(|e| format!("Failed to parse note types: {}", e))?;
+
+        for (id, mut model) in models {
+            let Ok(id) = id.parse() else {
+                continue;
+            };
+            model.flds.sort_by_key(|field| field.ord);
+            note_types.push(AnkiNoteType {
+                id,
+                name: model.name,
+                fields: model.flds.into_iter().map(|field| field.name).collect(),
+                notes_count: 0,
+            });
+        }
+    }
+
+    let counts: HashMap<i64, i64> = sqlx::query_as("SELECT mid, COUNT(*) FROM notes GROUP BY mid")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to count notes: {}", e))?
+        .into_iter()
+        .collect();
+
+    for note_type in &mut note_types {
+        note_type.notes_count = counts.get(&note_type.id).copied().unwrap_or(0);
+    }
+    note_types.sort_by_key(|note_type| (std::cmp::Reverse(note_type.notes_count), note_type.id));
+
+    Ok(note_types)
+}
+
+async fn read_deck(
+    conn: &mut SqliteConnection,
+    note_type_id: i64,
+    word_field: usize,
+    reading_field: usize,
+    meaning_field: Option<usize>,
+    with_reviews: bool,
+) -> Result<(DictionaryData, Vec<(String, Vec<Review>)>), String> {
+    let notes: Vec<(i64, String)> = sqlx::query_as(include_str!("../queries/anki_get_notes.sql"))
+        .bind(note_type_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to read notes: {}", e))?;
+
+    let mut data = DictionaryData::default();
+    let mut note_words: HashMap<i64, String> = HashMap::new();
+
+    for (note_id, fields) in notes {
+        let fields: Vec<&str> = fields.split(FIELD_SEPARATOR).collect();
+        let field = |index: usize| fields.get(index).map(|value| clean_field(value));
+
+        let record = note_record(
+            field(word_field).unwrap_or_default(),
+            field(reading_field).unwrap_or_default(),
+            meaning_field.and_then(field),
+        );
+        if add_word_list_record(&mut data, &record) {
+            note_words.insert(note_id, record.word);
+        }
+    }
+
+    let mut history: IndexMap<String, Vec<Review>> = IndexMap::new();
+    if with_reviews {
+        let reviews: Vec<(i64, i64, i64)> =
+            sqlx::query_as(include_str!("../queries/anki_get_reviews.sql"))
+                .bind(note_type_id)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to read reviews: {}", e))?;
+
+        // Review ids are answer timestamps in milliseconds
+        for (note_id, review_id, ease) in reviews {
+            let Some(word) = note_words.get(&note_id) else {
+                continue;
+            };
+            let Some(timestamp) = DateTime::from_timestamp_millis(review_id) else {
+                continue;
+            };
+
+            let rating = match ease {
+                1 => Rating::Again,
+                2 => Rating::Hard,
+                3 => Rating::Good,
+                _ => Rating::Easy,
+            };
+            history.entry(word.clone()).or_default().push(Review {
+                answer_stats_id: None,
+                timestamp,
+                is_correct: ease > 1,
+                rating,
+            });
+        }
+    }
+
+    Ok((data, history.into_iter().collect()))
+}
+
+#[tauri::command]
+pub async fn get_anki_note_types(path: &str) -> Result<Vec<AnkiNoteType>, String> {
+    let (collection, mut conn) = open_collection(path).await?;
+    let note_types = load_note_types(&mut conn).await;
+    let _ = conn.close().await;
+    drop(collection);
+
+    note_types
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn import_anki_deck<R: Runtime>(
+    app: AppHandle<R>,
+    path: &str,
+    name: &str,
+    note_type_id: i64,
+    word_field: usize,
+    reading_field: usize,
+    meaning_field: Option<usize>,
+    user_key: Option<&str>,
+    dictionary_id: Option<i64>,
+) -> Result<AnkiImportReport, String> {
+    let user_id = match user_key {
+        Some(user_key) => Some(get_user_id(user_key, None).await?),
+        None => None,
+    };
+    // Shared decks keep their note type ids, so only the dictionary chosen for the update is replaced
+    let guid = match dictionary_id {
+        Some(id) => get_dictionary_guid(id).await?,
+        None => Uuid::new_v4().to_string(),
+    };
+
+    let (collection, mut conn) = open_collection(path).await?;
+    let deck = read_deck(
+        &mut conn,
+        note_type_id,
+        word_field,
+        reading_field,
+        meaning_field,
+        user_id.is_some(),
+    )
+    .await;
+    let _ = conn.close().await;
+    drop(collection);
+
+    let (data, history) = deck?;
+    if data.words.is_empty() {
+        return Err("Deck contains no notes with a word and reading".to_string());
+    }
+
+    let (dictionary, summary) = import_dictionary_data(app, &data, &guid, name, None).await?;
+
+    let mut cards_count = 0;
+    let mut reviews_count = 0;
+    if let Some(user_id) = user_id {
+        (cards_count, reviews_count) = seed_cards_fsrs(user_id, &dictionary.guid, &history).await?;
+    }
+
+    Ok(AnkiImportReport {
+        dictionary,
+        summary,
+        cards_count,
+        reviews_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_are_cleaned_of_html() {
+        assert_eq!(
+            clean_field("<b>to eat</b><br>to consume<br/>"),
+            "to eat;to consume;"
+        );
+        assert_eq!(
+            clean_field("<div>A&amp;B</div><div>&lt;x&gt;</div>[sound:taberu.mp3]&nbsp;"),
+            "A&B;<x>;"
+        );
+        assert_eq!(clean_field("  食べる  "), "食べる");
+    }
+
+    #[test]
+    fn furigana_fields_become_word_list_records() {
+        let record = note_record("食[た]べる".to_string(), String::new(), None);
+        assert_eq!(record.word, "食べる");
+        assert_eq!(record.furigana.as_deref(), Some("食[た]べる"));
+
+        let record = note_record(
+            "日本".to_string(),
+            "日 本[にほん]".to_string(),
+            Some("Japan".to_string()),
+        );
+        assert_eq!(record.word, "日本");
+        assert_eq!(record.reading, "");
+        assert_eq!(record.furigana.as_deref(), Some("日 本[にほん]"));
+        assert_eq!(record.meanings.as_deref(), Some("Japan"));
+
+        let record = note_record("水".to_string(), "みず".to_string(), None);
+        assert_eq!(
+            (record.word.as_str(), record.reading.as_str()),
+            ("水", "みず")
+        );
+        assert_eq!(record.furigana, None);
+    }
+}
@@ -1,19 +1,35 @@
-use chrono::{NaiveDateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{query_file_as, sqlite::{SqliteConnectOptions, SqlitePool}};
-use std::sync::LazyLock;
+use sqlx::{query_file_as, sqlite::{SqliteConnectOptions, SqlitePool}, QueryBuilder, SqliteConnection};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Runtime};
+use uuid::Uuid;
+use wana_kana::ConvertJapanese;
 use rs_fsrs::{FSRS, Card, Rating, State};
 
-use crate::tools::get_executable_file_path;
-use sqlx::Acquire;
+use crate::fsrs::{self, Review};
+use sqlx::{Acquire, Connection};
 
-static DB_POOL: LazyLock<SqlitePool> = LazyLock::new(|| {
-    let path_str = get_executable_file_path()
+#[cfg(test)]
+mod tests;
+
+#[cfg(not(test))]
+fn main_db_path() -> std::path::PathBuf {
+    crate::tools::get_executable_file_path()
         .unwrap()
         .join("main.db")
-        .into_os_string()
-        .into_string()
-        .unwrap();
+}
+
+// Every test run starts from its own empty database
+#[cfg(test)]
+pub(crate) fn main_db_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("kanjilab_test_{}.db", std::process::id()))
+}
+
+static DB_POOL: LazyLock<SqlitePool> = LazyLock::new(|| {
+    let path_str = main_db_path().into_os_string().into_string().unwrap();
 
     let options = SqliteConnectOptions::new()
         .filename(path_str)
@@ -44,14 +60,33 @@ pub struct DictionaryInfo {
     is_exist: bool,
     stats_config: Option<String>,
     description: Option<String>,
+    version: Option<String>,
+    author: Option<String>,
+    license: Option<String>,
+    source_url: Option<String>,
+    created_at: Option<String>,
+    min_schema_version: Option<i64>,
 }
 
 #[tauri::command]
 pub async fn delete_dictionary(id: i64) -> Result<(), String> {
-    sqlx::query_file_as!(i64, "./queries/delete_dictionary.sql", id, id, id, id, id)
-        .execute(&*DB_POOL)
-        .await
-        .map_err(|e| format!("Failed to delete dictionary: {}", e))?;
+    sqlx::query_file_as!(
+        i64,
+        "./queries/delete_dictionary.sql",
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id
+    )
+    .execute(&*DB_POOL)
+    .await
+    .map_err(|e| format!("Failed to delete dictionary: {}", e))?;
 
     Ok(())
 }
@@ -66,17 +101,173 @@ pub async fn get_dictionaries() -> Result<Vec<DictionaryInfo>, String> {
     Ok(data)
 }
 
+// Purging needs the token handed out with the last listing
+static PURGE_TOKENS: LazyLock<Mutex<HashMap<i64, String>>> = LazyLock::new(Default::default);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedDictionary {
+    id: i64,
+    guid: String,
+    name: String,
+    description: Option<String>,
+    game_stats_count: i64,
+    answer_stats_count: i64,
+    cards_count: i64,
+    review_log_count: i64,
+    purge_token: String,
+}
+
+#[tauri::command]
+pub async fn get_deleted_dictionaries() -> Result<Vec<DeletedDictionary>, String> {
+    struct RawData {
+        id: i64,
+        guid: String,
+        name: String,
+        description: Option<String>,
+        game_stats_count: i64,
+        answer_stats_count: i64,
+        cards_count: i64,
+        review_log_count: i64,
+    }
+
+    let data = sqlx::query_file_as!(RawData, "./queries/get_deleted_dictionaries.sql")
+        .fetch_all(&*DB_POOL)
+        .await
+        .map_err(|e| format!("Failed to get deleted dictionaries: {}", e))?;
+
+    let mut tokens = PURGE_TOKENS.lock().map_err(|e| e.to_string())?;
+    tokens.clear();
+
+    let dictionaries = data
+        .into_iter()
+        .map(|raw| {
+            let purge_token = Uuid::new_v4().to_string();
+            tokens.insert(raw.id, purge_token.clone());
+            DeletedDictionary {
+                id: raw.id,
+                guid: raw.guid,
+                name: raw.name,
+                description: raw.description,
+                game_stats_count: raw.game_stats_count,
+                answer_stats_count: raw.answer_stats_count,
+                cards_count: raw.cards_count,
+                review_log_count: raw.review_log_count,
+                purge_token,
+            }
+        })
+        .collect();
+
+    Ok(dictionaries)
+}
+
 #[tauri::command]
-pub async fn import_dictionary(dict_path: &str) -> Result<(), String> {
-    let dict_pool = SqlitePool::connect(dict_path)
+pub async fn purge_dictionary(id: i64, purge_token: &str) -> Result<(), String> {
+    let is_confirmed = PURGE_TOKENS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&id)
+        .is_some_and(|token| token == purge_token);
+    if !is_confirmed {
+        return Err("Invalid purge token, list deleted dictionaries again".to_string());
+    }
+
+    let dictionary = sqlx::query_file_as!(DictionaryInfo, "./queries/get_dictionary_by_id.sql", id)
+        .fetch_optional(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Dictionary not found for id: {}", id))?;
+
+    if dictionary.is_exist {
+        return Err(format!("Dictionary '{}' is not deleted", dictionary.name));
+    }
+
+    let mut tx = DB_POOL
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    sqlx::query_file!(
+        "./queries/purge_dictionary.sql",
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id,
+        id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to purge dictionary: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    PURGE_TOKENS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&id);
+
+    Ok(())
+}
+
+// The save dialog has already confirmed overwriting
+pub async fn create_dictionary_database(path: &str) -> Result<SqlitePool, String> {
+    if std::path::Path::new(path).exists() {
+        std::fs::remove_file(path).map_err(|e| format!("Failed to replace file: {}", e))?;
+    }
+
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    let dict_pool = SqlitePool::connect_with(options)
         .await
-        .map_err(|e| format!("Failed to connect to dictionary database: {}", e))?;
+        .map_err(|e| format!("Failed to create dictionary database: {}", e))?;
 
     sqlx::migrate!("./migrations/dict")
         .run(&dict_pool)
         .await
         .map_err(|e| format!("Failed to migrate dictionary database: {}", e))?;
 
+    Ok(dict_pool)
+}
+
+pub(crate) async fn get_dictionary_guid(id: i64) -> Result<String, String> {
+    let dictionary = sqlx::query_file_as!(DictionaryInfo, "./queries/get_dictionary_by_id.sql", id)
+        .fetch_optional(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Dictionary not found for id: {}", id))?;
+
+    if !dictionary.is_exist {
+        return Err(format!("Dictionary '{}' was deleted", dictionary.name));
+    }
+
+    Ok(dictionary.guid)
+}
+
+#[tauri::command]
+pub async fn export_dictionary(id: i64, path: &str) -> Result<(), String> {
+    let dictionary = sqlx::query_file_as!(DictionaryInfo, "./queries/get_dictionary_by_id.sql", id)
+        .fetch_optional(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Dictionary not found for id: {}", id))?;
+
+    if !dictionary.is_exist {
+        return Err(format!("Dictionary '{}' was deleted", dictionary.name));
+    }
+
+    let dict_pool = create_dictionary_database(path).await?;
     dict_pool.close().await;
 
     let pool = (*DB_POOL).to_owned();
@@ -87,7 +278,7 @@ pub async fn import_dictionary(dict_path: &str) -> Result<(), String> {
         .map_err(|e| format!("Failed to acquire connection: {}", e))?;
 
     sqlx::query("ATTACH DATABASE ? AS dict_db")
-        .bind(dict_path)
+        .bind(path)
         .execute(&mut *conn)
         .await
         .map_err(|e| format!("Failed to attach dictionary: {}", e))?;
@@ -97,18 +288,18 @@ pub async fn import_dictionary(dict_path: &str) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-    let sql = include_str!("../queries/import_dictionary.sql");
+    let sql = include_str!("../queries/export_dictionary.sql");
 
-    let import_result = sqlx::query(sql).execute(&mut *tx).await;
+    let export_result = sqlx::query(sql).bind(id).execute(&mut *tx).await;
 
-    let result = match import_result {
+    let result = match export_result {
         Ok(_) => tx
             .commit()
             .await
             .map_err(|e| format!("Failed to commit transaction: {}", e)),
         Err(e) => {
             let _ = tx.rollback().await;
-            Err(format!("Import failed: {}", e))
+            Err(format!("Export failed: {}", e))
         }
     };
 
@@ -117,390 +308,2469 @@ pub async fn import_dictionary(dict_path: &str) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to detach dictionary: {}", e))?;
 
+    if result.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+
     result
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+// Dictionary file format written by this build,
+// files requiring a newer one are rejected
+pub const DICTIONARY_SCHEMA_VERSION: i64 = 2;
+
+// Added in schema version 2, older files don't have them
+const DICTIONARY_METADATA_COLUMNS: [&str; 6] = [
+    "version",
+    "author",
+    "license",
+    "source_url",
+    "created_at",
+    "min_schema_version",
+];
+
+static IMPORT_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct WordPartExample {
-    word: String,
-    frequency: Option<i64>,
-    reading: String,
+pub enum ImportStage {
+    DeleteOld,
+    Dictionary,
+    Words,
+    Readings,
+    PartReadings,
+    Links,
+    ReadingTags,
+    Senses,
+    Kanji,
+    WordKanji,
+    SearchIndex,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct WordPartInfo {
-    word_part: String,
-    word_part_reading: String,
-    examples: Vec<WordPartExample>,
+pub struct ImportProgress {
+    stage: ImportStage,
+    rows_processed: u64,
+    rows_total: u64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ReadingWithParts {
-    reading: String,
-    parts: Vec<WordPartInfo>,
+pub struct DictionaryImportSummary {
+    words_added: u64,
+    words_removed: u64,
+    words_changed: u64,
+    readings_added: u64,
+    readings_removed: u64,
+    readings_changed: u64,
+    warnings: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WordWithReadings {
-    word: String,
-    meanings: Vec<Vec<Vec<String>>>,
-    readings: Vec<ReadingWithParts>,
+#[derive(Debug, sqlx::FromRow)]
+struct DictionaryMetadata {
+    guid: String,
+    name: String,
+    stats_config: Option<String>,
+    description: Option<String>,
+    version: Option<String>,
+    author: Option<String>,
+    license: Option<String>,
+    source_url: Option<String>,
+    created_at: Option<String>,
+    min_schema_version: Option<i64>,
 }
 
-#[tauri::command]
-pub async fn get_words(
-    count: i64,
-    min_frequency: i64,
-    max_frequency: Option<i64>,
-    word_part: Option<&str>,
-    word_part_reading: Option<&str>,
-    examples_count: i64,
-    dictionary_id: i64,
-) -> Result<Vec<WordWithReadings>, String> {
-    const GLOSS_SEPARATOR: &str = "␞";
-    const SENSE_SEPARATOR: &str = "␝";
-    const KEB_SEPARATOR: &str = "␟";
-    const READINGS_SEPARATOR: &str = ",";
+async fn read_dictionary_metadata(
+    conn: &mut SqliteConnection,
+    schema: &str,
+) -> Result<Option<DictionaryMetadata>, sqlx::Error> {
+    let columns: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM pragma_table_info('dictionary_info', $1)")
+            .bind(schema)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    let metadata_columns: Vec<String> = DICTIONARY_METADATA_COLUMNS
+        .into_iter()
+        .map(|column| {
+            if columns.iter().any(|name| name == column) {
+                column.to_string()
+            } else {
+                format!("NULL AS {}", column)
+            }
+        })
+        .collect();
+
+    sqlx::query_as(&format!(
+        "SELECT guid, name, stats_config, description, {} FROM {}.dictionary_info LIMIT 1",
+        metadata_columns.join(", "),
+        schema
+    ))
+    .fetch_optional(&mut *conn)
+    .await
+}
 
-    #[allow(dead_code)]
-    struct RawData {
-        id: i64,
-        word: String,
-        meanings: String,
-        word_readings: String,
-        word_reading_ids: String,
+// Numeric parts are compared as numbers, so 1.10 is newer than 1.9
+fn compare_versions(left: &str, right: &str) -> std::cmp::Ordering {
+    fn parts(version: &str) -> Vec<&str> {
+        version
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect()
     }
 
-    let raw_data = if let Some(part) = word_part {
-        query_file_as!(
-            RawData,
-            "./queries/get_words_with_parts.sql",
-            count,
-            min_frequency,
-            max_frequency,
-            part,
-            word_part_reading,
-            dictionary_id
-        )
-        .fetch_all(&*DB_POOL)
-        .await
-        .map_err(|e| e.to_string())?
-    } else {
-        query_file_as!(
-            RawData,
-            "./queries/get_words.sql",
-            count,
-            min_frequency,
-            max_frequency,
-            dictionary_id
-        )
-        .fetch_all(&*DB_POOL)
-        .await
-        .map_err(|e| e.to_string())?
-    };
-
-    let mut result = Vec::new();
-
-    for raw_word in raw_data {
-        let reading_ids: Vec<i64> = raw_word
-            .word_reading_ids
-            .split(READINGS_SEPARATOR)
-            .filter_map(|id| id.parse().ok())
-            .collect();
+    let left = parts(left);
+    let right = parts(right);
+    for (left_part, right_part) in left.iter().zip(&right) {
+        let ordering = match (left_part.parse::<u64>(), right_part.parse::<u64>()) {
+            (Ok(left_number), Ok(right_number)) => left_number.cmp(&right_number),
+            _ => left_part.cmp(right_part),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
 
-        let readings: Vec<String> = raw_word
-            .word_readings
-            .split(READINGS_SEPARATOR)
-            .map(String::from)
-            .collect();
+    left.len().cmp(&right.len())
+}
 
-        let meanings: Vec<Vec<Vec<String>>> = raw_word
-            .meanings
-            .split(KEB_SEPARATOR)
-            .map(|keb| {
-                keb.split(SENSE_SEPARATOR)
-                    .map(|sense| sense.split(GLOSS_SEPARATOR).map(String::from).collect())
-                    .collect()
-            })
-            .collect();
+fn emit_import_progress<R: Runtime>(
+    app: &AppHandle<R>,
+    stage: ImportStage,
+    rows_processed: u64,
+    rows_total: u64,
+) {
+    let progress = ImportProgress {
+        stage,
+        rows_processed,
+        rows_total,
+    };
 
-        let mut readings_with_parts = Vec::new();
-        for (reading_id, reading) in reading_ids.into_iter().zip(readings.into_iter()) {
-            match get_reading_with_parts(reading_id, reading, examples_count, dictionary_id).await {
-                Ok(reading_with_parts) => readings_with_parts.push(reading_with_parts),
-                Err(e) => eprintln!("Error getting reading with parts: {}", e),
-            }
-        }
+    if let Err(e) = app.emit("dictionary_import_progress", progress) {
+        eprintln!("Failed to emit import progress: {}", e);
+    }
+}
 
-        result.push(WordWithReadings {
-            word: raw_word.word,
-            meanings,
-            readings: readings_with_parts,
-        });
+fn check_import_cancelled() -> Result<(), String> {
+    if IMPORT_CANCELLED.load(Ordering::SeqCst) {
+        Err("Import cancelled".to_string())
+    } else {
+        Ok(())
     }
+}
 
-    Ok(result)
+#[tauri::command]
+pub fn cancel_dictionary_import() {
+    IMPORT_CANCELLED.store(true, Ordering::SeqCst);
 }
 
 #[tauri::command]
-pub async fn get_words_count(
-    min_frequency: i64,
-    max_frequency: Option<i64>,
-    word_part: Option<&str>,
-    word_part_reading: Option<&str>,
-    dictionary_id: i64,
-) -> Result<i64, String> {
-    #[allow(dead_code)]
-    struct RawData {
-        count: i64,
+pub async fn import_dictionary<R: Runtime>(
+    app: AppHandle<R>,
+    dict_path: &str,
+) -> Result<DictionaryImportSummary, String> {
+    IMPORT_CANCELLED.store(false, Ordering::SeqCst);
+
+    let report = validate_dictionary(dict_path).await?;
+    if !report.is_valid {
+        let messages: Vec<&str> = report
+            .errors
+            .iter()
+            .map(|issue| issue.message.as_str())
+            .collect();
+        return Err(format!("Dictionary validation failed: {}", messages.join("; ")));
     }
 
-    let data = query_file_as!(
-        RawData,
-        "./queries/get_words_count.sql",
-        min_frequency,
-        max_frequency,
-        word_part,
-        word_part_reading,
-        dictionary_id
-    )
-    .fetch_one(&*DB_POOL)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(data.count)
-}
+    let pool = (*DB_POOL).to_owned();
 
-async fn get_reading_with_parts(
-    reading_id: i64,
-    reading: String,
-    examples_count: i64,
-    dictionary_id: i64,
-) -> Result<ReadingWithParts, String> {
-    #[allow(dead_code)]
-    struct RawPartData {
-        part_id: i64,
-        word_part: String,
-        word_part_reading: String,
-        top_words: String,
-        top_words_frequencies: String,
-        top_words_readings: String,
-    }
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| format!("Failed to acquire connection: {}", e))?;
 
-    let raw_part_data = query_file_as!(
-        RawPartData,
-        "./queries/get_word_parts_examples.sql",
-        reading_id,
-        examples_count,
-        dictionary_id,
-        dictionary_id
-    )
-    .fetch_all(&*DB_POOL)
-    .await
-    .map_err(|e| e.to_string())?;
+    sqlx::query("ATTACH DATABASE ? AS dict_db")
+        .bind(dict_path)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to attach dictionary: {}", e))?;
 
-    let mut parts = Vec::new();
+    let mut tx = conn
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-    for raw_part in raw_part_data {
-        let words: Vec<&str> = raw_part.top_words.split(',').collect();
-        let frequencies: Vec<&str> = raw_part.top_words_frequencies.split(',').collect();
-        let readings: Vec<&str> = raw_part.top_words_readings.split(',').collect();
+    let import_result = import_dictionary_stages(&app, &mut tx).await;
 
-        let examples: Vec<WordPartExample> = words
-            .into_iter()
-            .zip(frequencies.into_iter())
-            .zip(readings.into_iter())
-            .map(|((word, frequency), reading)| WordPartExample {
-                word: word.to_string(),
-                frequency: frequency.parse().ok(),
-                reading: reading.to_string(),
+    let result = match import_result {
+        Ok(summary) => tx
+            .commit()
+            .await
+            .map(|_| {
+                clear_word_order();
+                summary
             })
-            .collect();
+            .map_err(|e| format!("Failed to commit transaction: {}", e)),
+        Err(e) => {
+            let _ = tx.rollback().await;
+            Err(e)
+        }
+    };
+
+    sqlx::query("DETACH DATABASE dict_db")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to detach dictionary: {}", e))?;
+
+    result
+}
+
+// Merges into the existing rows matched by text,
+// so unchanged words and readings keep their ids
+async fn import_dictionary_stages<R: Runtime>(
+    app: &AppHandle<R>,
+    conn: &mut SqliteConnection,
+) -> Result<DictionaryImportSummary, String> {
+    let mut changed_readings: HashSet<i64> = sqlx::query_scalar(include_str!(
+        "../queries/import_dictionary_changed_readings.sql"
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Import failed: {}", e))?
+    .into_iter()
+    .collect();
+
+    // Files written before reading flags were added keep the flags already installed
+    let has_reading_flags = has_dictionary_column(conn, "word_reading", "is_common").await?;
+    if has_reading_flags {
+        let changed_flags: Vec<i64> = sqlx::query_scalar(include_str!(
+            "../queries/import_dictionary_changed_reading_flags.sql"
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Import failed: {}", e))?;
+        changed_readings.extend(changed_flags);
+    }
+
+    let mut summary = DictionaryImportSummary {
+        readings_changed: changed_readings.len() as u64,
+        ..Default::default()
+    };
+
+    // Senses and kanji have no ids of their own worth keeping, they are always rewritten
+    let senses_removed =
+        execute_import_query(conn, include_str!("../queries/import_dictionary_delete_senses.sql"))
+            .await?;
+    let word_kanji_removed = execute_import_query(
+        conn,
+        include_str!("../queries/import_dictionary_delete_word_kanji.sql"),
+    )
+    .await?;
+    let kanji_removed =
+        execute_import_query(conn, include_str!("../queries/import_dictionary_delete_kanji.sql"))
+            .await?;
+    let reading_tags_removed = execute_import_query(
+        conn,
+        include_str!("../queries/import_dictionary_delete_reading_tags.sql"),
+    )
+    .await?;
+    let links_removed =
+        execute_import_query(conn, include_str!("../queries/import_dictionary_delete_links.sql"))
+            .await?;
+    summary.readings_removed = execute_import_query(
+        conn,
+        include_str!("../queries/import_dictionary_delete_readings.sql"),
+    )
+    .await?;
+    summary.words_removed =
+        execute_import_query(conn, include_str!("../queries/import_dictionary_delete_words.sql"))
+            .await?;
+    let part_readings_removed = execute_import_query(
+        conn,
+        include_str!("../queries/import_dictionary_delete_part_readings.sql"),
+    )
+    .await?;
+
+    let rows_deleted = senses_removed
+        + word_kanji_removed
+        + kanji_removed
+        + reading_tags_removed
+        + links_removed
+        + summary.readings_removed
+        + summary.words_removed
+        + part_readings_removed;
+    emit_import_progress(app, ImportStage::DeleteOld, rows_deleted, rows_deleted);
+    check_import_cancelled()?;
+
+    let metadata = read_dictionary_metadata(conn, "dict_db")
+        .await
+        .map_err(|e| format!("Import failed: {}", e))?
+        .ok_or_else(|| "Import failed: no dictionary_info row".to_string())?;
+
+    let installed_version: Option<String> = sqlx::query_scalar(
+        "SELECT version FROM main.dictionary WHERE guid = $1 AND is_exist",
+    )
+    .bind(&metadata.guid)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| format!("Import failed: {}", e))?
+    .flatten();
+
+    if let (Some(version), Some(installed_version)) = (&metadata.version, &installed_version)
+        && compare_versions(version, installed_version).is_lt()
+    {
+        summary.warnings.push(format!(
+            "Version {} is older than the installed version {}",
+            version, installed_version
+        ));
+    }
+
+    let inserted = sqlx::query(include_str!("../queries/import_dictionary_info.sql"))
+        .bind(&metadata.guid)
+        .bind(&metadata.name)
+        .bind(&metadata.stats_config)
+        .bind(&metadata.description)
+        .bind(&metadata.version)
+        .bind(&metadata.author)
+        .bind(&metadata.license)
+        .bind(&metadata.source_url)
+        .bind(&metadata.created_at)
+        .bind(metadata.min_schema_version)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Import failed: {}", e))?;
+    emit_import_progress(app, ImportStage::Dictionary, inserted.rows_affected(), 1);
+
+    let mut stages = vec![
+        (
+            ImportStage::Words,
+            "word",
+            include_str!("../queries/import_dictionary_words.sql"),
+        ),
+        (
+            ImportStage::Readings,
+            "word_reading",
+            include_str!("../queries/import_dictionary_readings.sql"),
+        ),
+        (
+            ImportStage::PartReadings,
+            "word_part_reading",
+            include_str!("../queries/import_dictionary_part_readings.sql"),
+        ),
+        (
+            ImportStage::Links,
+            "word_reading_word_part_reading",
+            include_str!("../queries/import_dictionary_links.sql"),
+        ),
+    ];
+
+    // Files written before reading tags, sense tags and kanji were added have no such tables
+    if has_dictionary_table(conn, "word_reading_tag").await? {
+        stages.push((
+            ImportStage::ReadingTags,
+            "word_reading_tag",
+            include_str!("../queries/import_dictionary_reading_tags.sql"),
+        ));
+    }
+    if has_dictionary_table(conn, "word_sense").await? {
+        stages.push((
+            ImportStage::Senses,
+            "word_sense",
+            include_str!("../queries/import_dictionary_senses.sql"),
+        ));
+    }
+    if has_dictionary_table(conn, "kanji").await? {
+        stages.push((
+            ImportStage::Kanji,
+            "kanji",
+            include_str!("../queries/import_dictionary_kanji.sql"),
+        ));
+    }
+
+    summary.words_changed =
+        execute_import_query(conn, include_str!("../queries/import_dictionary_update_words.sql"))
+            .await?;
+
+    for (stage, table, sql) in stages {
+        let rows_added = import_dictionary_table(app, conn, stage, table, sql).await?;
+        match stage {
+            ImportStage::Words => summary.words_added = rows_added,
+            ImportStage::Readings => summary.readings_added = rows_added,
+            _ => {}
+        }
+    }
+
+    if has_reading_flags {
+        execute_import_query(
+            conn,
+            include_str!("../queries/import_dictionary_reading_flags.sql"),
+        )
+        .await?;
+    }
+
+    check_import_cancelled()?;
+    let dictionary_id: i64 = sqlx::query_scalar("SELECT id FROM main.dictionary WHERE guid = $1")
+        .bind(&metadata.guid)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Import failed: {}", e))?;
+    emit_import_progress(app, ImportStage::WordKanji, 0, 1);
+    let word_kanji_added = sqlx::query_file!("./queries/insert_word_kanji.sql", dictionary_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Import failed: {}", e))?
+        .rows_affected();
+    emit_import_progress(
+        app,
+        ImportStage::WordKanji,
+        word_kanji_added,
+        word_kanji_added,
+    );
+
+    check_import_cancelled()?;
+    emit_import_progress(app, ImportStage::SearchIndex, 0, 1);
+    let rows_indexed = rebuild_search_index(conn, dictionary_id).await?;
+    emit_import_progress(app, ImportStage::SearchIndex, rows_indexed, rows_indexed);
+
+    Ok(summary)
+}
+
+async fn has_dictionary_column(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+) -> Result<bool, String> {
+    sqlx::query_scalar("SELECT COUNT(*) > 0 FROM pragma_table_info($1, 'dict_db') WHERE name = $2")
+        .bind(table)
+        .bind(column)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Import failed: {}", e))
+}
+
+async fn has_dictionary_table(conn: &mut SqliteConnection, table: &str) -> Result<bool, String> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM dict_db.sqlite_master WHERE type = 'table' AND name = $1",
+    )
+    .bind(table)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| format!("Import failed: {}", e))
+}
+
+async fn execute_import_query(conn: &mut SqliteConnection, sql: &str) -> Result<u64, String> {
+    let result = sqlx::query(sql)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Import failed: {}", e))?;
+
+    Ok(result.rows_affected())
+}
+
+// Copies the table in batches of rowids, so progress is reported
+// and cancellation is checked between batches.
+// Rowids may have gaps, so each batch ends at the last of the next BATCH_SIZE rowids.
+// Rows already present are skipped, returns the number of added rows
+async fn import_dictionary_table<R: Runtime>(
+    app: &AppHandle<R>,
+    conn: &mut SqliteConnection,
+    stage: ImportStage,
+    table: &str,
+    sql: &str,
+) -> Result<u64, String> {
+    const BATCH_SIZE: i64 = 10000;
+
+    let rows_total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM dict_db.{}", table))
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to count {} rows: {}", table, e))?;
+
+    let batch_sql = format!(
+        "SELECT COUNT(*), MAX(rowid) FROM (SELECT rowid FROM dict_db.{} WHERE rowid > $1 ORDER BY rowid LIMIT $2)",
+        table
+    );
+
+    let rows_total = rows_total as u64;
+    let mut rows_added = 0;
+    let mut rows_processed = 0;
+    emit_import_progress(app, stage, 0, rows_total);
+
+    let mut last_rowid = i64::MIN;
+    loop {
+        check_import_cancelled()?;
+
+        let (batch_rows, batch_end): (i64, Option<i64>) = sqlx::query_as(&batch_sql)
+            .bind(last_rowid)
+            .bind(BATCH_SIZE)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to read {} rows: {}", table, e))?;
+
+        let Some(batch_end) = batch_end else {
+            break;
+        };
+
+        let result = sqlx::query(sql)
+            .bind(last_rowid)
+            .bind(batch_end)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Import failed: {}", e))?;
+
+        rows_added += result.rows_affected();
+        rows_processed += batch_rows as u64;
+        last_rowid = batch_end;
+
+        emit_import_progress(app, stage, rows_processed.min(rows_total), rows_total);
+    }
+
+    Ok(rows_added)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    code: String,
+    message: String,
+    count: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictionaryValidationReport {
+    is_valid: bool,
+    errors: Vec<ValidationIssue>,
+    warnings: Vec<ValidationIssue>,
+}
+
+impl DictionaryValidationReport {
+    fn error(&mut self, code: &str, count: i64, message: &str) {
+        if count > 0 {
+            self.errors.push(ValidationIssue {
+                code: code.to_string(),
+                message: format!("{} ({})", message, count),
+                count,
+            });
+            self.is_valid = false;
+        }
+    }
+
+    fn warning(&mut self, code: &str, count: i64, message: &str) {
+        if count > 0 {
+            self.warnings.push(ValidationIssue {
+                code: code.to_string(),
+                message: format!("{} ({})", message, count),
+                count,
+            });
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DictionaryValidationCounts {
+    dictionary_info_count: i64,
+    empty_dictionary_info_count: i64,
+    words_count: i64,
+    duplicate_words_count: i64,
+    empty_meanings_count: i64,
+    words_without_readings_count: i64,
+    dangling_readings_count: i64,
+    duplicate_readings_count: i64,
+    duplicate_part_readings_count: i64,
+    unused_part_readings_count: i64,
+    dangling_link_readings_count: i64,
+    dangling_link_parts_count: i64,
+}
+
+const DICTIONARY_TABLES: [&str; 5] = [
+    "dictionary_info",
+    "word",
+    "word_reading",
+    "word_part_reading",
+    "word_reading_word_part_reading",
+];
+
+#[tauri::command]
+pub async fn validate_dictionary(dict_path: &str) -> Result<DictionaryValidationReport, String> {
+    let options = SqliteConnectOptions::new()
+        .filename(dict_path)
+        .read_only(true);
+
+    let mut conn = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("Failed to open dictionary database: {}", e))?;
+
+    let report = validate_dictionary_connection(&mut conn).await;
+    let _ = conn.close().await;
+
+    report
+}
+
+async fn validate_dictionary_connection(
+    conn: &mut SqliteConnection,
+) -> Result<DictionaryValidationReport, String> {
+    let mut report = DictionaryValidationReport {
+        is_valid: true,
+        errors: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    let tables: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table'")
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to read dictionary schema: {}", e))?;
+
+    let missing_tables: Vec<&str> = DICTIONARY_TABLES
+        .into_iter()
+        .filter(|table| !tables.iter().any(|name| name == table))
+        .collect();
+    if !missing_tables.is_empty() {
+        report.error(
+            "missingTables",
+            missing_tables.len() as i64,
+            &format!("Missing tables: {}", missing_tables.join(", ")),
+        );
+        return Ok(report);
+    }
+
+    let counts: DictionaryValidationCounts =
+        match sqlx::query_as(include_str!("../queries/validate_dictionary.sql"))
+            .fetch_one(&mut *conn)
+            .await
+        {
+            Ok(counts) => counts,
+            Err(e) => {
+                report.error("invalidSchema", 1, &format!("Invalid schema: {}", e));
+                return Ok(report);
+            }
+        };
+
+    if counts.dictionary_info_count == 0 {
+        report.error("missingDictionaryInfo", 1, "No dictionary_info row");
+    }
+    if counts.dictionary_info_count > 1 {
+        report.error(
+            "multipleDictionaryInfo",
+            counts.dictionary_info_count,
+            "More than one dictionary_info row",
+        );
+    }
+    report.error(
+        "emptyDictionaryInfo",
+        counts.empty_dictionary_info_count,
+        "dictionary_info rows without guid or name",
+    );
+    report.error(
+        "duplicateWords",
+        counts.duplicate_words_count,
+        "Words listed more than once",
+    );
+    report.error(
+        "danglingReadings",
+        counts.dangling_readings_count,
+        "Readings pointing to missing words",
+    );
+    report.error(
+        "duplicateReadings",
+        counts.duplicate_readings_count,
+        "Readings listed more than once for the same word",
+    );
+    report.error(
+        "duplicatePartReadings",
+        counts.duplicate_part_readings_count,
+        "Word part readings listed more than once",
+    );
+    report.error(
+        "danglingLinkReadings",
+        counts.dangling_link_readings_count,
+        "Links pointing to missing readings",
+    );
+    report.error(
+        "danglingLinkParts",
+        counts.dangling_link_parts_count,
+        "Links pointing to missing word part readings",
+    );
+
+    let metadata = read_dictionary_metadata(conn, "main")
+        .await
+        .map_err(|e| format!("Failed to read dictionary info: {}", e))?;
+    if let Some(min_schema_version) = metadata.and_then(|metadata| metadata.min_schema_version)
+        && min_schema_version > DICTIONARY_SCHEMA_VERSION
+    {
+        report.error(
+            "newerSchema",
+            1,
+            &format!(
+                "Dictionary requires schema version {}, supported up to {}",
+                min_schema_version, DICTIONARY_SCHEMA_VERSION
+            ),
+        );
+    }
+
+    if counts.words_count == 0 {
+        report.warning("noWords", 1, "Dictionary has no words");
+    }
+    report.warning(
+        "wordsWithoutReadings",
+        counts.words_without_readings_count,
+        "Words without readings are never asked",
+    );
+    report.warning(
+        "emptyMeanings",
+        counts.empty_meanings_count,
+        "Words without meanings",
+    );
+    report.warning(
+        "unusedPartReadings",
+        counts.unused_part_readings_count,
+        "Word part readings not linked to any reading",
+    );
+
+    let stats_configs: Vec<Option<String>> =
+        sqlx::query_scalar("SELECT stats_config FROM dictionary_info")
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to read dictionary info: {}", e))?;
+    let invalid_stats_configs = stats_configs
+        .into_iter()
+        .flatten()
+        .filter(|config| serde_json::from_str::<serde_json::Value>(config).is_err())
+        .count();
+    report.warning(
+        "invalidStatsConfig",
+        invalid_stats_configs as i64,
+        "stats_config is not valid JSON",
+    );
+
+    Ok(report)
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordPartExample {
+    word: String,
+    frequency: Option<i64>,
+    reading: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordPartInfo {
+    word_part: String,
+    word_part_reading: String,
+    examples: Vec<WordPartExample>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingWithParts {
+    reading: String,
+    is_common: bool,
+    is_rare: bool,
+    is_irregular: bool,
+    pitch_accents: Vec<i64>,
+    parts: Vec<WordPartInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SenseInfo {
+    glosses: Vec<String>,
+    pos: Vec<String>,
+    misc: Vec<String>,
+    field: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordEntry {
+    priority: Vec<String>,
+    senses: Vec<SenseInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordWithReadings {
+    word: String,
+    meanings: Vec<Vec<Vec<String>>>,
+    entries: Vec<WordEntry>,
+    readings: Vec<ReadingWithParts>,
+}
+
+#[allow(dead_code)]
+struct RawWordData {
+    id: i64,
+    word: String,
+    meanings: String,
+    word_readings: String,
+    word_reading_ids: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordPartFilter {
+    word_part: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    word_part_reading: Option<String>,
+}
+
+// Every set criterion narrows the selection. Word parts and reading lengths
+// are checked per reading, and only the matching readings are returned.
+// Kanji criteria need every kanji of the word to be at JLPT N`min_kanji_jlpt_n` or easier
+// and of a grade of at most `max_kanji_grade`, kanji without metadata fail them.
+// Criteria left at their defaults are not serialized, so stored filters stay comparable
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WordFilter {
+    min_frequency: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_frequency: Option<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    word_parts: Vec<WordPartFilter>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    exclude_word_parts: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    include_words: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    exclude_words: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_word_length: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_word_length: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_reading_length: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_reading_length: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_readings_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_readings_count: Option<i64>,
+    // Ateji or irregular kanji, kana or okurigana, see word_reading_tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    irregular_readings: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_kanji_jlpt_n: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_kanji_grade: Option<i64>,
+}
+
+// List criteria are passed to the queries as JSON arrays
+struct WordFilterLists {
+    word_parts: String,
+    exclude_word_parts: String,
+    include_words: String,
+    exclude_words: String,
+}
+
+fn filter_list<T: Serialize>(items: &[T]) -> Result<String, String> {
+    serde_json::to_string(items).map_err(|e| format!("Invalid word filter: {}", e))
+}
+
+impl WordFilter {
+    fn lists(&self) -> Result<WordFilterLists, String> {
+        Ok(WordFilterLists {
+            word_parts: filter_list(&self.word_parts)?,
+            exclude_word_parts: filter_list(&self.exclude_word_parts)?,
+            include_words: filter_list(&self.include_words)?,
+            exclude_words: filter_list(&self.exclude_words)?,
+        })
+    }
+
+    // KANJIDIC2 keeps the JLPT levels from before 2010, 4 (easiest) to 1. N5, N4 and N1
+    // match old levels 4, 3 and 1. N3 has no list of its own, its kanji are in old level 2
+    // together with N2, so N3 is approximated by old level 2 and also admits N2 kanji
+    fn min_kanji_old_jlpt(&self) -> Option<i64> {
+        self.min_kanji_jlpt_n.map(|level| match level {
+            5.. => 4,
+            4 => 3,
+            2 | 3 => 2,
+            _ => 1,
+        })
+    }
+}
+
+// Each bias adds to the weight of a word by its score between 0 and 1, all zero means uniform selection
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WordSampling {
+    // Rarest frequency in the candidates, or no frequency at all
+    rarity_bias: f64,
+    // Share of the user's wrong answers to the word in this dictionary, unused with a seed
+    missed_bias: f64,
+    // Readings of the word relative to the candidate with the most readings
+    readings_bias: f64,
+}
+
+impl WordSampling {
+    fn validate(&self) -> Result<(), String> {
+        let biases = [self.rarity_bias, self.missed_bias, self.readings_bias];
+        if biases.iter().any(|bias| !bias.is_finite() || *bias < 0.0) {
+            return Err("Invalid word sampling: biases must be non-negative numbers".to_string());
+        }
+
+        Ok(())
+    }
+
+    fn is_uniform(&self) -> bool {
+        self.rarity_bias == 0.0 && self.missed_bias == 0.0 && self.readings_bias == 0.0
+    }
+
+    // Seeded games replay and page through one fixed order, which new answers would shift
+    fn for_seed(self, seed: Option<i64>) -> Self {
+        match seed {
+            Some(_) => Self {
+                missed_bias: 0.0,
+                ..self
+            },
+            None => self,
+        }
+    }
+}
+
+// Candidates come from the query as one JSON array of [id, word, frequency, readings, answers, wrong answers]
+#[derive(Deserialize)]
+struct WordCandidate {
+    id: i64,
+    word: String,
+    frequency: Option<i64>,
+    readings_count: i64,
+    answers_count: i64,
+    wrong_count: i64,
+}
+
+// Seeded orders hash the word text instead of row ids, so they match across installs of the same dictionary
+const SEED_MODULUS: i64 = 2147483647;
+const SEED_MULTIPLIER: i64 = 48271;
+
+fn normalize_seed(seed: i64) -> i64 {
+    seed.rem_euclid(SEED_MODULUS)
+}
+
+// Uniform value in (0, 1] from every character of the word and the seed
+fn seeded_uniform(word: &str, seed: i64) -> f64 {
+    let mut hash = seed;
+    for c in word.chars() {
+        hash = (hash * SEED_MULTIPLIER + c as i64) % SEED_MODULUS;
+    }
+    hash = (hash * SEED_MULTIPLIER + seed) % SEED_MODULUS;
+    let key = (hash * hash + seed) % SEED_MODULUS;
+
+    (SEED_MODULUS - key) as f64 / SEED_MODULUS as f64
+}
+
+// Weighted sampling without replacement (Efraimidis-Spirakis), ordering every candidate by -ln(u) / weight
+fn sample_words(
+    candidates: &[WordCandidate],
+    sampling: &WordSampling,
+    seed: Option<i64>,
+    offset: i64,
+    count: i64,
+) -> Vec<i64> {
+    let frequencies = candidates
+        .iter()
+        .filter_map(|candidate| candidate.frequency);
+    let min_frequency = frequencies.clone().min().unwrap_or(0);
+    let max_frequency = frequencies.max().unwrap_or(0);
+    let max_readings_count = candidates
+        .iter()
+        .map(|candidate| candidate.readings_count)
+        .max()
+        .unwrap_or(0);
+
+    let mut keyed: Vec<(f64, &WordCandidate)> = candidates
+        .iter()
+        .map(|candidate| {
+            let rarity = match candidate.frequency {
+                Some(frequency) if max_frequency > min_frequency => {
+                    (frequency - min_frequency) as f64 / (max_frequency - min_frequency) as f64
+                }
+                Some(_) => 0.0,
+                None => 1.0,
+            };
+            let missed = if candidate.answers_count > 0 {
+                candidate.wrong_count as f64 / candidate.answers_count as f64
+            } else {
+                0.0
+            };
+            let readings = if max_readings_count > 1 {
+                (candidate.readings_count - 1) as f64 / (max_readings_count - 1) as f64
+            } else {
+                0.0
+            };
+            let weight = 1.0
+                + sampling.rarity_bias * rarity
+                + sampling.missed_bias * missed
+                + sampling.readings_bias * readings;
+
+            let uniform = match seed {
+                Some(seed) => seeded_uniform(&candidate.word, seed),
+                None => 1.0 - rand::random::<f64>(),
+            };
+
+            (-uniform.ln() / weight, candidate)
+        })
+        .collect();
+
+    let offset = offset.max(0) as usize;
+    let compare = |(a_key, a): &(f64, &WordCandidate), (b_key, b): &(f64, &WordCandidate)| {
+        a_key.total_cmp(b_key).then_with(|| a.word.cmp(&b.word))
+    };
+    // Only the requested page needs sorting
+    let end = offset.saturating_add(count.max(0) as usize);
+    if end < keyed.len() {
+        keyed.select_nth_unstable_by(end, compare);
+        keyed.truncate(end);
+    }
+    keyed.sort_by(compare);
+
+    keyed
+        .into_iter()
+        .skip(offset)
+        .take(count.max(0) as usize)
+        .map(|(_, candidate)| candidate.id)
+        .collect()
+}
+
+// Without a limit every candidate is returned, with one that many in random order
+async fn get_word_candidates(
+    filter: &WordFilter,
+    lists: &WordFilterLists,
+    user_id: Option<i64>,
+    limit: Option<i64>,
+    dictionary_id: i64,
+) -> Result<Vec<WordCandidate>, String> {
+    struct RawCandidates {
+        candidates: String,
+    }
+
+    let min_kanji_old_jlpt = filter.min_kanji_old_jlpt();
+    let raw_candidates = query_file_as!(
+        RawCandidates,
+        "./queries/get_words_candidates.sql",
+        filter.min_frequency,
+        filter.max_frequency,
+        dictionary_id,
+        lists.word_parts,
+        lists.exclude_word_parts,
+        lists.include_words,
+        lists.exclude_words,
+        filter.min_word_length,
+        filter.max_word_length,
+        filter.min_reading_length,
+        filter.max_reading_length,
+        filter.min_readings_count,
+        filter.max_readings_count,
+        filter.irregular_readings,
+        min_kanji_old_jlpt,
+        filter.max_kanji_grade,
+        user_id,
+        limit
+    )
+    .fetch_one(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
 
-        parts.push(WordPartInfo {
-            word_part: raw_part.word_part,
-            word_part_reading: raw_part.word_part_reading,
-            examples,
+    serde_json::from_str(&raw_candidates.candidates).map_err(|e| e.to_string())
+}
+
+// Orders of the running game, so later batches skip the dictionary scan.
+// Unseeded games keep the missed counts of their first batch until the next game starts
+static WORD_ORDER: Mutex<Option<CachedWordOrder>> = Mutex::new(None);
+
+enum WordOrder {
+    Seeded(Vec<i64>),
+    Sampled(Vec<WordCandidate>),
+}
+
+// Keyed by the dictionary, filter, sampling, seed and user of the game
+struct CachedWordOrder {
+    key: String,
+    order: Arc<WordOrder>,
+}
+
+fn cached_word_order(key: &str) -> Option<Arc<WordOrder>> {
+    let cached = WORD_ORDER.lock().ok()?;
+    cached
+        .as_ref()
+        .filter(|cached| cached.key == key)
+        .map(|cached| cached.order.clone())
+}
+
+fn cache_word_order(key: String, order: WordOrder) -> Arc<WordOrder> {
+    let order = Arc::new(order);
+    if let Ok(mut cached) = WORD_ORDER.lock() {
+        *cached = Some(CachedWordOrder {
+            key,
+            order: order.clone(),
         });
     }
-
-    Ok(ReadingWithParts { reading, parts })
+    order
+}
+
+fn clear_word_order() {
+    if let Ok(mut cached) = WORD_ORDER.lock() {
+        *cached = None;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn get_words(
+    count: i64,
+    filter: WordFilter,
+    sampling: WordSampling,
+    seed: Option<i64>,
+    offset: Option<i64>,
+    user_key: Option<&str>,
+    examples_count: i64,
+    dictionary_id: i64,
+) -> Result<Vec<WordWithReadings>, String> {
+    sampling.validate()?;
+    let lists = filter.lists()?;
+    let seed = seed.map(normalize_seed);
+    let sampling = sampling.for_seed(seed);
+    // Without a profile or answers yet, no word counts as missed
+    let user_id = match user_key {
+        Some(user_key) if sampling.missed_bias > 0.0 => find_user_id(user_key).await?,
+        _ => None,
+    };
+    let offset = offset.unwrap_or(0);
+
+    let word_ids: Vec<i64> = if sampling.is_uniform() && seed.is_none() {
+        // Uniform picks without a seed are left to SQLite
+        get_word_candidates(&filter, &lists, user_id, Some(count), dictionary_id)
+            .await?
+            .iter()
+            .map(|candidate| candidate.id)
+            .collect()
+    } else {
+        let order_key = serde_json::to_string(&(dictionary_id, &filter, &sampling, seed, user_id))
+            .map_err(|e| e.to_string())?;
+        let order = match cached_word_order(&order_key) {
+            Some(order) => order,
+            None => {
+                let candidates =
+                    get_word_candidates(&filter, &lists, user_id, None, dictionary_id).await?;
+                let order = match seed {
+                    Some(_) => {
+                        WordOrder::Seeded(sample_words(&candidates, &sampling, seed, 0, i64::MAX))
+                    }
+                    None => WordOrder::Sampled(candidates),
+                };
+                cache_word_order(order_key, order)
+            }
+        };
+
+        match &*order {
+            WordOrder::Seeded(ids) => ids
+                .iter()
+                .skip(offset.max(0) as usize)
+                .take(count.max(0) as usize)
+                .copied()
+                .collect(),
+            WordOrder::Sampled(candidates) => {
+                sample_words(candidates, &sampling, None, offset, count)
+            }
+        }
+    };
+    let word_ids = serde_json::to_string(&word_ids).map_err(|e| e.to_string())?;
+
+    let raw_data = query_file_as!(
+        RawWordData,
+        "./queries/get_words.sql",
+        word_ids,
+        filter.min_reading_length,
+        filter.max_reading_length,
+        lists.word_parts
+    )
+    .fetch_all(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    build_words_with_readings(raw_data, examples_count, dictionary_id).await
+}
+
+// Due cards and new words are limited by the same filter as get_words
+#[tauri::command]
+pub async fn get_due_words(
+    count: i64,
+    user_key: &str,
+    new_words_limit: i64,
+    filter: WordFilter,
+    examples_count: i64,
+    dictionary_id: i64,
+) -> Result<Vec<WordWithReadings>, String> {
+    let lists = filter.lists()?;
+    let min_kanji_old_jlpt = filter.min_kanji_old_jlpt();
+    // A profile without any answers yet has no cards, so every word is new
+    let user_id = find_user_id(user_key).await?;
+    let now = Utc::now().naive_utc();
+
+    let raw_data = query_file_as!(
+        RawWordData,
+        "./queries/get_due_words.sql",
+        filter.min_frequency,
+        filter.max_frequency,
+        dictionary_id,
+        lists.word_parts,
+        lists.exclude_word_parts,
+        lists.include_words,
+        lists.exclude_words,
+        filter.min_word_length,
+        filter.max_word_length,
+        filter.min_reading_length,
+        filter.max_reading_length,
+        filter.min_readings_count,
+        filter.max_readings_count,
+        filter.irregular_readings,
+        min_kanji_old_jlpt,
+        filter.max_kanji_grade,
+        user_id,
+        count,
+        now,
+        new_words_limit
+    )
+    .fetch_all(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    build_words_with_readings(raw_data, examples_count, dictionary_id).await
+}
+
+async fn build_words_with_readings(
+    raw_data: Vec<RawWordData>,
+    examples_count: i64,
+    dictionary_id: i64,
+) -> Result<Vec<WordWithReadings>, String> {
+    const GLOSS_SEPARATOR: &str = "␞";
+    const SENSE_SEPARATOR: &str = "␝";
+    const KEB_SEPARATOR: &str = "␟";
+    const READINGS_SEPARATOR: &str = ",";
+    const TAG_SEPARATOR: char = ',';
+
+    let word_ids: Vec<i64> = raw_data.iter().map(|raw_word| raw_word.id).collect();
+    let word_ids = serde_json::to_string(&word_ids).map_err(|e| e.to_string())?;
+    let sense_tags: HashMap<(i64, i64, i64), _> =
+        sqlx::query_file!("./queries/get_words_senses.sql", word_ids)
+            .fetch_all(&*DB_POOL)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|row| ((row.word_id, row.entry_index, row.sense_index), row))
+            .collect();
+    let split_tags = |tags: &str| -> Vec<String> {
+        tags.split(TAG_SEPARATOR)
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect()
+    };
+
+    let reading_ids: Vec<Vec<i64>> = raw_data
+        .iter()
+        .map(|raw_word| {
+            raw_word
+                .word_reading_ids
+                .split(READINGS_SEPARATOR)
+                .filter_map(|id| id.parse().ok())
+                .collect()
+        })
+        .collect();
+    let all_reading_ids = reading_ids.concat();
+    let mut reading_parts =
+        get_readings_parts(&all_reading_ids, examples_count, dictionary_id).await?;
+    let all_reading_ids = serde_json::to_string(&all_reading_ids).map_err(|e| e.to_string())?;
+    let reading_flags: HashMap<i64, _> =
+        sqlx::query_file!("./queries/get_readings_flags.sql", all_reading_ids)
+            .fetch_all(&*DB_POOL)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|row| (row.id, row))
+            .collect();
+
+    let mut result = Vec::new();
+
+    for (raw_word, reading_ids) in raw_data.into_iter().zip(reading_ids) {
+        let readings: Vec<String> = raw_word
+            .word_readings
+            .split(READINGS_SEPARATOR)
+            .map(String::from)
+            .collect();
+
+        let meanings: Vec<Vec<Vec<String>>> = raw_word
+            .meanings
+            .split(KEB_SEPARATOR)
+            .map(|keb| {
+                keb.split(SENSE_SEPARATOR)
+                    .map(|sense| sense.split(GLOSS_SEPARATOR).map(String::from).collect())
+                    .collect()
+            })
+            .collect();
+
+        let entries: Vec<WordEntry> = meanings
+            .iter()
+            .enumerate()
+            .map(|(entry_index, senses)| {
+                let tags_of = |sense_index: usize| {
+                    sense_tags.get(&(raw_word.id, entry_index as i64, sense_index as i64))
+                };
+                let priority = (0..senses.len())
+                    .find_map(tags_of)
+                    .map(|tags| split_tags(&tags.priority))
+                    .unwrap_or_default();
+                let senses = senses
+                    .iter()
+                    .enumerate()
+                    .map(|(sense_index, glosses)| {
+                        let tags = tags_of(sense_index);
+                        SenseInfo {
+                            glosses: glosses.clone(),
+                            pos: tags.map(|tags| split_tags(&tags.pos)).unwrap_or_default(),
+                            misc: tags.map(|tags| split_tags(&tags.misc)).unwrap_or_default(),
+                            field: tags.map(|tags| split_tags(&tags.field)).unwrap_or_default(),
+                        }
+                    })
+                    .collect();
+                WordEntry { priority, senses }
+            })
+            .collect();
+
+        let readings_with_parts: Vec<ReadingWithParts> = reading_ids
+            .into_iter()
+            .zip(readings)
+            .map(|(reading_id, reading)| {
+                let flags = reading_flags.get(&reading_id);
+                ReadingWithParts {
+                    reading,
+                    is_common: flags.is_some_and(|flags| flags.is_common),
+                    is_rare: flags.is_some_and(|flags| flags.is_rare),
+                    is_irregular: flags.is_some_and(|flags| flags.is_irregular),
+                    pitch_accents: flags
+                        .and_then(|flags| flags.pitch_accent.as_deref())
+                        .map(|accents| {
+                            accents
+                                .split(TAG_SEPARATOR)
+                                .filter_map(|accent| accent.parse().ok())
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    parts: reading_parts.remove(&reading_id).unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        result.push(WordWithReadings {
+            word: raw_word.word,
+            meanings,
+            entries,
+            readings: readings_with_parts,
+        });
+    }
+
+    Ok(result)
+}
+
+const WORD_DETAILS_EXAMPLES_COUNT: i64 = 5;
+
+// Answers only keep the word, so past rounds are looked up by it
+#[tauri::command]
+pub async fn get_word_details(
+    word: &str,
+    dictionary_id: i64,
+) -> Result<Option<WordWithReadings>, String> {
+    let raw_data = query_file_as!(
+        RawWordData,
+        "./queries/get_word_details.sql",
+        word,
+        dictionary_id
+    )
+    .fetch_all(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let words =
+        build_words_with_readings(raw_data, WORD_DETAILS_EXAMPLES_COUNT, dictionary_id).await?;
+
+    Ok(words.into_iter().next())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanjiInfo {
+    kanji: String,
+    jlpt: Option<i64>,
+    grade: Option<i64>,
+    stroke_count: Option<i64>,
+    on_readings: Vec<String>,
+    kun_readings: Vec<String>,
+    meanings: Vec<String>,
+}
+
+// Returns the kanji of `text` in order, characters without metadata are skipped
+#[tauri::command]
+pub async fn get_kanji_info(text: &str, dictionary_id: i64) -> Result<Vec<KanjiInfo>, String> {
+    const READINGS_SEPARATOR: char = ',';
+    const MEANINGS_SEPARATOR: char = '␞';
+
+    let mut characters: Vec<String> = Vec::new();
+    for character in text.chars().map(String::from) {
+        if !characters.contains(&character) {
+            characters.push(character);
+        }
+    }
+    let characters_json = serde_json::to_string(&characters).map_err(|e| e.to_string())?;
+
+    let split = |value: &str, separator: char| -> Vec<String> {
+        value
+            .split(separator)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect()
+    };
+
+    let mut kanji: HashMap<String, KanjiInfo> =
+        sqlx::query_file!("./queries/get_kanji_info.sql", characters_json, dictionary_id)
+            .fetch_all(&*DB_POOL)
+            .await
+            .map_err(|e| format!("Failed to get kanji info: {}", e))?
+            .into_iter()
+            .map(|row| {
+                let info = KanjiInfo {
+                    kanji: row.kanji.clone(),
+                    jlpt: row.jlpt,
+                    grade: row.grade,
+                    stroke_count: row.stroke_count,
+                    on_readings: split(&row.on_readings, READINGS_SEPARATOR),
+                    kun_readings: split(&row.kun_readings, READINGS_SEPARATOR),
+                    meanings: split(&row.meanings, MEANINGS_SEPARATOR),
+                };
+                (row.kanji, info)
+            })
+            .collect();
+
+    Ok(characters
+        .iter()
+        .filter_map(|character| kanji.remove(character))
+        .collect())
+}
+
+const SEARCH_INDEX_BATCH_SIZE: usize = 1000;
+
+// Trigram tokens need at least three characters to be matched through the index
+const MIN_MATCH_LENGTH: usize = 3;
+
+static SEARCH_INDEXED: LazyLock<Mutex<HashSet<i64>>> = LazyLock::new(Default::default);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordSearchPage {
+    words: Vec<WordWithReadings>,
+    total_count: i64,
+}
+
+// Readings are indexed as romaji too, so partial romaji input still matches
+async fn rebuild_search_index(
+    conn: &mut SqliteConnection,
+    dictionary_id: i64,
+) -> Result<u64, String> {
+    sqlx::query("DELETE FROM word_search WHERE dictionary_id = $1")
+        .bind(dictionary_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to clear search index: {}", e))?;
+
+    let words = sqlx::query_file!("./queries/get_search_index_words.sql", dictionary_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to read words for search index: {}", e))?;
+
+    for chunk in words.chunks(SEARCH_INDEX_BATCH_SIZE) {
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO word_search (rowid, word, readings, romaji, meanings, dictionary_id) ",
+        );
+        builder.push_values(chunk, |mut row, word| {
+            let romaji: Vec<String> = word
+                .word_readings
+                .split(' ')
+                .map(|reading| reading.to_romaji())
+                .collect();
+            row.push_bind(word.id)
+                .push_bind(&word.word)
+                .push_bind(&word.word_readings)
+                .push_bind(romaji.join(" "))
+                .push_bind(word.meanings.replace(['␞', '␝', '␟'], "\n"))
+                .push_bind(dictionary_id);
+        });
+        builder
+            .build()
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to write search index: {}", e))?;
+    }
+
+    Ok(words.len() as u64)
+}
+
+// Dictionaries imported before the search index existed get it on first search
+async fn ensure_search_index(dictionary_id: i64) -> Result<(), String> {
+    if SEARCH_INDEXED
+        .lock()
+        .map_err(|e| e.to_string())?
+        .contains(&dictionary_id)
+    {
+        return Ok(());
+    }
+
+    let is_indexed: bool =
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM word_search WHERE dictionary_id = $1)")
+            .bind(dictionary_id)
+            .fetch_one(&*DB_POOL)
+            .await
+            .map_err(|e| format!("Failed to read search index: {}", e))?;
+
+    if !is_indexed {
+        let mut tx = DB_POOL
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+        rebuild_search_index(&mut tx, dictionary_id).await?;
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    }
+
+    SEARCH_INDEXED
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(dictionary_id);
+
+    Ok(())
+}
+
+fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+fn match_phrase(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+// Matches the word and meanings by substring and readings as kana or romaji,
+// exact word matches come first and then the most frequent words
+#[tauri::command]
+pub async fn search_words(
+    query: &str,
+    page: i64,
+    page_size: i64,
+    examples_count: i64,
+    dictionary_id: i64,
+) -> Result<WordSearchPage, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(WordSearchPage {
+            words: Vec::new(),
+            total_count: 0,
+        });
+    }
+
+    ensure_search_index(dictionary_id).await?;
+
+    let kana = query.to_hiragana();
+    let ids: Vec<i64> = if query.chars().count() >= MIN_MATCH_LENGTH
+        && kana.chars().count() >= MIN_MATCH_LENGTH
+    {
+        let expression = format!(
+            "{{word meanings romaji}} : {} OR readings : {}",
+            match_phrase(query),
+            match_phrase(&kana)
+        );
+        sqlx::query_scalar(include_str!("../queries/search_words_match.sql"))
+            .bind(expression)
+            .bind(dictionary_id)
+            .bind(query)
+            .fetch_all(&*DB_POOL)
+            .await
+    } else {
+        sqlx::query_scalar(include_str!("../queries/search_words_like.sql"))
+            .bind(dictionary_id)
+            .bind(like_pattern(query))
+            .bind(like_pattern(&kana))
+            .bind(like_pattern(&query.to_lowercase()))
+            .bind(query)
+            .fetch_all(&*DB_POOL)
+            .await
+    }
+    .map_err(|e| format!("Failed to search words: {}", e))?;
+
+    let total_count = ids.len() as i64;
+    let page_ids: Vec<i64> = ids
+        .into_iter()
+        .skip((page.max(0) * page_size.max(0)) as usize)
+        .take(page_size.max(0) as usize)
+        .collect();
+    let page_ids = serde_json::to_string(&page_ids).map_err(|e| e.to_string())?;
+
+    let raw_data = query_file_as!(RawWordData, "./queries/get_words_by_ids.sql", page_ids)
+        .fetch_all(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?;
+    let words = build_words_with_readings(raw_data, examples_count, dictionary_id).await?;
+
+    Ok(WordSearchPage { words, total_count })
+}
+
+#[tauri::command]
+pub async fn get_words_count(filter: WordFilter, dictionary_id: i64) -> Result<i64, String> {
+    #[allow(dead_code)]
+    struct RawData {
+        count: i64,
+    }
+
+    let lists = filter.lists()?;
+    let min_kanji_old_jlpt = filter.min_kanji_old_jlpt();
+    let data = query_file_as!(
+        RawData,
+        "./queries/get_words_count.sql",
+        filter.min_frequency,
+        filter.max_frequency,
+        dictionary_id,
+        lists.word_parts,
+        lists.exclude_word_parts,
+        lists.include_words,
+        lists.exclude_words,
+        filter.min_word_length,
+        filter.max_word_length,
+        filter.min_reading_length,
+        filter.max_reading_length,
+        filter.min_readings_count,
+        filter.max_readings_count,
+        filter.irregular_readings,
+        min_kanji_old_jlpt,
+        filter.max_kanji_grade
+    )
+    .fetch_one(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(data.count)
+}
+
+// Parts of all readings are loaded in one query, keyed by reading id
+async fn get_readings_parts(
+    reading_ids: &[i64],
+    examples_count: i64,
+    dictionary_id: i64,
+) -> Result<HashMap<i64, Vec<WordPartInfo>>, String> {
+    #[allow(dead_code)]
+    struct RawPartData {
+        reading_id: i64,
+        part_id: i64,
+        word_part: String,
+        word_part_reading: String,
+        top_words: String,
+        top_words_frequencies: String,
+        top_words_readings: String,
+    }
+
+    let reading_ids = serde_json::to_string(reading_ids).map_err(|e| e.to_string())?;
+    let raw_part_data = query_file_as!(
+        RawPartData,
+        "./queries/get_readings_parts_examples.sql",
+        reading_ids,
+        examples_count,
+        dictionary_id
+    )
+    .fetch_all(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut parts: HashMap<i64, Vec<WordPartInfo>> = HashMap::new();
+
+    for raw_part in raw_part_data {
+        parts
+            .entry(raw_part.reading_id)
+            .or_default()
+            .push(word_part_info(
+                raw_part.word_part,
+                raw_part.word_part_reading,
+                &raw_part.top_words,
+                &raw_part.top_words_frequencies,
+                &raw_part.top_words_readings,
+            ));
+    }
+
+    Ok(parts)
+}
+
+// Examples come as comma separated lists, with NULL for missing frequencies
+fn word_part_info(
+    word_part: String,
+    word_part_reading: String,
+    top_words: &str,
+    top_words_frequencies: &str,
+    top_words_readings: &str,
+) -> WordPartInfo {
+    let examples = top_words
+        .split(',')
+        .zip(top_words_frequencies.split(','))
+        .zip(top_words_readings.split(','))
+        .map(|((word, frequency), reading)| WordPartExample {
+            word: word.to_string(),
+            frequency: frequency.parse().ok(),
+            reading: reading.to_string(),
+        })
+        .collect();
+
+    WordPartInfo {
+        word_part,
+        word_part_reading,
+        examples,
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsInfo {
+    correct_count: i64,
+    wrong_count: i64,
+}
+
+#[tauri::command]
+pub async fn get_overall_stats(user_key: &str, dictionary_id: i64) -> Result<StatsInfo, String> {
+    let user_id = get_user_id(user_key, None).await?;
+    let data = query_file_as!(
+        StatsInfo,
+        "./queries/get_overall_stats.sql",
+        user_id,
+        dictionary_id
+    )
+    .fetch_one(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(data)
+}
+
+#[tauri::command]
+pub async fn get_font_id(name: &str) -> Result<i64, String> {
+    struct RawData {
+        id: i64,
+    }
+
+    let font_id = sqlx::query_file_as!(RawData, "./queries/get_or_create_font.sql", name, name)
+        .fetch_one(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(font_id.id)
+}
+
+#[tauri::command]
+pub async fn get_user_id(key: &str, last_name: Option<&str>) -> Result<i64, String> {
+    struct RawData {
+        id: i64,
+    }
+
+    if let Some(name) = last_name {
+        let user_id = sqlx::query_file_as!(
+            RawData,
+            "./queries/get_or_create_user.sql",
+            key,
+            name,
+            name,
+            key,
+            key
+        )
+        .fetch_one(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok(user_id.id)
+    } else {
+        find_user_id(key)
+            .await?
+            .ok_or_else(|| "User not found".to_string())
+    }
+}
+
+// A missing profile is not an error, failing to read one is
+async fn find_user_id(key: &str) -> Result<Option<i64>, String> {
+    let userdata = sqlx::query_file_as!(User, "./queries/get_userdata_by_key.sql", key)
+        .fetch_optional(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(userdata.map(|user| user.id))
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn add_answer_stats(
+    game_stats_id: i64,
+    user_key: &str,
+    user_name: &str,
+    word: &str,
+    word_reading: &str,
+    duration: Option<i64>,
+    is_correct: bool,
+    round_index: i64,
+    font_id: i64,
+) -> Result<i64, String> {
+    struct RawData {
+        id: i64,
+    }
+
+    let user_id = get_user_id(user_key, Some(user_name))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result = query_file_as!(
+        RawData,
+        "./queries/add_answer_stats.sql",
+        game_stats_id,
+        user_id,
+        word,
+        word_reading,
+        duration,
+        is_correct,
+        round_index,
+        font_id,
+    )
+    .fetch_one(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(result.id)
+}
+
+// The frequency range and the first word part are kept in their own columns for older records.
+// Review mode games serve due cards, so their sampling is not recorded
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn add_game_stats(
+    rounds_count: i64,
+    round_duration: i64,
+    filter: WordFilter,
+    sampling: WordSampling,
+    seed: Option<i64>,
+    is_review_mode: bool,
+    font_id: Option<i64>,
+    dictionary_id: i64,
+) -> Result<i64, String> {
+    struct RawData {
+        id: i64,
+    }
+
+    let word_part = filter.word_parts.first().map(|part| &part.word_part);
+    let word_part_reading = filter
+        .word_parts
+        .first()
+        .and_then(|part| part.word_part_reading.as_ref());
+    let word_filter =
+        serde_json::to_string(&filter).map_err(|e| format!("Invalid word filter: {}", e))?;
+    let seed = seed.map(normalize_seed);
+    sampling.validate()?;
+    let sampling = sampling.for_seed(seed);
+    clear_word_order();
+    let word_sampling = if sampling.is_uniform() || is_review_mode {
+        None
+    } else {
+        Some(
+            serde_json::to_string(&sampling)
+                .map_err(|e| format!("Invalid word sampling: {}", e))?,
+        )
+    };
+
+    let result = sqlx::query_file_as!(
+        RawData,
+        "./queries/add_game_stats.sql",
+        rounds_count,
+        round_duration,
+        filter.min_frequency,
+        filter.max_frequency,
+        word_part,
+        word_part_reading,
+        font_id,
+        dictionary_id,
+        word_filter,
+        seed,
+        word_sampling,
+        is_review_mode
+    )
+    .fetch_one(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(result.id)
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnswerStreaks {
+    game_id: i64,
+    length: i64,
+}
+
+#[tauri::command]
+pub async fn get_answer_streaks(
+    min_frequency: i64,
+    max_frequency: Option<i64>,
+    count: i64,
+    user_key: &str,
+    dictionary_id: i64,
+) -> Result<Vec<AnswerStreaks>, String> {
+    let user_id = get_user_id(user_key, None).await?;
+    let data = sqlx::query_file_as!(
+        AnswerStreaks,
+        "./queries/get_answer_streaks.sql",
+        min_frequency,
+        max_frequency,
+        count,
+        user_id,
+        dictionary_id
+    )
+    .fetch_all(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(data)
+}
+
+#[tauri::command]
+pub async fn get_word_parts(dictionary_id: i64) -> Result<Vec<String>, String> {
+    let data = sqlx::query_file!("./queries/get_word_parts.sql", dictionary_id)
+        .map(|row| row.word_part)
+        .fetch_all(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(data)
+}
+
+#[tauri::command]
+pub async fn get_word_part_readings(
+    word_part: String,
+    dictionary_id: i64,
+) -> Result<Vec<String>, String> {
+    let data = sqlx::query_file!(
+        "./queries/get_word_part_readings.sql",
+        word_part,
+        dictionary_id
+    )
+    .map(|row| row.word_part_reading)
+    .fetch_all(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(data)
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameStats {
+    id: i64,
+    rounds_count: i64,
+    round_duration: i64,
+    min_frequency: i64,
+    max_frequency: Option<i64>,
+    font_id: Option<i64>,
+    font: Option<String>,
+    dictionary_id: i64,
+    dictionary: String,
+    real_rounds_count: i64,
+    users_count: i64,
+    timestamp: NaiveDateTime,
+    word_filter: Option<String>,
+    seed: Option<i64>,
+    word_sampling: Option<String>,
+    is_review_mode: bool,
+}
+
+#[tauri::command]
+pub async fn get_all_games_stats(dictionary_id: i64) -> Result<Vec<GameStats>, String> {
+    let data = sqlx::query_file_as!(
+        GameStats,
+        "./queries/get_all_games_stats.sql",
+        dictionary_id
+    )
+    .fetch_all(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(data)
+}
+
+#[tauri::command]
+pub async fn get_game_stats(id: i64) -> Result<GameStats, String> {
+    let data = sqlx::query_file_as!(GameStats, "./queries/get_game_stats.sql", id)
+        .fetch_optional(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(gs) = data {
+        Ok(gs)
+    } else {
+        Err(format!("Game stats not found for id: {}", id))
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct StatsInfo {
-    correct_count: i64,
-    wrong_count: i64,
+pub struct AnswerStats {
+    id: i64,
+    game_stats_id: i64,
+    user_id: i64,
+    user: String,
+    word: String,
+    word_reading: String,
+    duration: Option<i64>,
+    is_correct: bool,
+    round_index: i64,
+    timestamp: NaiveDateTime,
+    font_id: i64,
+    font: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct AnswerStatsDB {
+    id: i64,
+    game_stats_id: i64,
+    user_id: i64,
+    user: String,
+    word: String,
+    word_reading: String,
+    duration: Option<i64>,
+    is_correct: i64,
+    round_index: i64,
+    timestamp: NaiveDateTime,
+    font_id: i64,
+    font: String,
+}
+
+impl From<AnswerStatsDB> for AnswerStats {
+    fn from(db: AnswerStatsDB) -> Self {
+        AnswerStats {
+            id: db.id,
+            game_stats_id: db.game_stats_id,
+            user_id: db.user_id,
+            user: db.user,
+            word: db.word,
+            word_reading: db.word_reading,
+            duration: db.duration,
+            is_correct: db.is_correct != 0,
+            round_index: db.round_index,
+            timestamp: db.timestamp,
+            font_id: db.font_id,
+            font: db.font,
+        }
+    }
 }
 
 #[tauri::command]
-pub async fn get_overall_stats(user_key: &str, dictionary_id: i64) -> Result<StatsInfo, String> {
-    let user_id = get_user_id(user_key, None).await?;
-    let data = query_file_as!(
-        StatsInfo,
-        "./queries/get_overall_stats.sql",
-        user_id,
+pub async fn get_answer_stats_by_game(game_stats_id: i64) -> Result<Vec<AnswerStats>, String> {
+    let data = sqlx::query_file_as!(
+        AnswerStatsDB,
+        "./queries/get_answer_stats_by_game.sql",
+        game_stats_id
+    )
+    .fetch_all(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(data.into_iter().map(AnswerStats::from).collect())
+}
+
+#[tauri::command]
+pub async fn get_all_answer_stats(dictionary_id: i64) -> Result<Vec<AnswerStats>, String> {
+    let data = sqlx::query_file_as!(
+        AnswerStatsDB,
+        "./queries/get_all_answer_stats.sql",
         dictionary_id
     )
-    .fetch_one(&*DB_POOL)
+    .fetch_all(&*DB_POOL)
     .await
     .map_err(|e| e.to_string())?;
 
-    Ok(data)
+    Ok(data.into_iter().map(AnswerStats::from).collect())
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    id: i64,
+    key: String,
+    username: String,
 }
 
 #[tauri::command]
-pub async fn get_font_id(name: &str) -> Result<i64, String> {
-    struct RawData {
-        id: i64,
+pub async fn get_userdata_by_id(user_id: i64) -> Result<User, String> {
+    let data = sqlx::query_file_as!(User, "./queries/get_userdata_by_id.sql", user_id)
+        .fetch_optional(&*DB_POOL)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(user) = data {
+        Ok(User {
+            id: user_id,
+            username: user.username,
+            key: user.key,
+        })
+    } else {
+        Err(format!("User not found with id: {}", user_id))
     }
+}
 
-    let font_id = sqlx::query_file_as!(RawData, "./queries/get_or_create_font.sql", name, name)
-        .fetch_one(&*DB_POOL)
+#[tauri::command]
+pub async fn get_all_users() -> Result<Vec<User>, String> {
+    let users = sqlx::query_file_as!(User, "./queries/get_all_users.sql")
+        .fetch_all(&*DB_POOL)
         .await
         .map_err(|e| e.to_string())?;
+    Ok(users)
+}
 
-    Ok(font_id.id)
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CardFsrsRecord {
+    id: Option<i64>,
+    word: String,
+    due: NaiveDateTime,
+    stability: f64,
+    difficulty: f64,
+    elapsed_days: i64,
+    scheduled_days: i64,
+    reps: i64,
+    lapses: i64,
+    state: i64,
+    last_review: NaiveDateTime,
 }
 
-#[tauri::command]
-pub async fn get_user_id(key: &str, last_name: Option<&str>) -> Result<i64, String> {
-    struct RawData {
-        id: i64,
+impl From<CardFsrsRecord> for Card {
+    fn from(record: CardFsrsRecord) -> Self {
+        Card {
+            due: record.due.and_utc(),
+            stability: record.stability,
+            difficulty: record.difficulty,
+            elapsed_days: record.elapsed_days,
+            scheduled_days: record.scheduled_days,
+            reps: record.reps as i32,
+            lapses: record.lapses as i32,
+            state: match record.state {
+                0 => State::New,
+                1 => State::Learning,
+                2 => State::Review,
+                3 => State::Relearning,
+                _ => State::New,
+            },
+            last_review: record.last_review.and_utc(),
+        }
     }
+}
 
-    if let Some(name) = last_name {
-        let user_id = sqlx::query_file_as!(
-            RawData,
-            "./queries/get_or_create_user.sql",
-            key,
-            name,
-            name,
-            key,
-            key
-        )
-        .fetch_one(&*DB_POOL)
+fn card_to_record(card: &Card, word: String, id: Option<i64>) -> CardFsrsRecord {
+    CardFsrsRecord {
+        id,
+        word,
+        due: card.due.naive_utc(),
+        stability: card.stability,
+        difficulty: card.difficulty,
+        elapsed_days: card.elapsed_days,
+        scheduled_days: card.scheduled_days,
+        reps: card.reps as i64,
+        lapses: card.lapses as i64,
+        state: match card.state {
+            State::New => 0,
+            State::Learning => 1,
+            State::Review => 2,
+            State::Relearning => 3,
+        },
+        last_review: card.last_review.naive_utc(),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsrsSettings {
+    easy_threshold: f64,
+    hard_threshold: f64,
+    parameters: Option<Vec<f64>>,
+}
+
+struct FsrsSettingsDB {
+    easy_threshold: f64,
+    hard_threshold: f64,
+    parameters: Option<String>,
+}
+
+impl From<FsrsSettingsDB> for FsrsSettings {
+    fn from(db: FsrsSettingsDB) -> Self {
+        FsrsSettings {
+            easy_threshold: db.easy_threshold,
+            hard_threshold: db.hard_threshold,
+            parameters: db
+                .parameters
+                .and_then(|parameters| serde_json::from_str(&parameters).ok()),
+        }
+    }
+}
+
+impl Default for FsrsSettings {
+    fn default() -> Self {
+        FsrsSettings {
+            easy_threshold: 0.25,
+            hard_threshold: 0.75,
+            parameters: None,
+        }
+    }
+}
+
+impl FsrsSettings {
+    fn fsrs(&self) -> FSRS {
+        fsrs::scheduler(self.parameters.as_deref())
+    }
+
+    fn weights(&self) -> Vec<f64> {
+        self.parameters.clone().unwrap_or_else(fsrs::default_weights)
+    }
+
+    // Thresholds are fractions of the round duration spent before a correct answer
+    fn rating(&self, is_correct: bool, duration: Option<i64>, round_duration: i64) -> Rating {
+        if !is_correct {
+            return Rating::Again;
+        }
+
+        let Some(duration) = duration.filter(|_| round_duration > 0) else {
+            return Rating::Good;
+        };
+
+        let ratio = duration as f64 / round_duration as f64;
+        if ratio <= self.easy_threshold {
+            Rating::Easy
+        } else if ratio >= self.hard_threshold {
+            Rating::Hard
+        } else {
+            Rating::Good
+        }
+    }
+}
+
+async fn load_fsrs_settings(user_id: i64) -> Result<FsrsSettings, String> {
+    let data = query_file_as!(FsrsSettingsDB, "./queries/get_fsrs_settings.sql", user_id)
+        .fetch_optional(&*DB_POOL)
         .await
         .map_err(|e| e.to_string())?;
 
-        Ok(user_id.id)
-    } else {
-        let userdata = sqlx::query_file_as!(User, "./queries/get_userdata_by_key.sql", key)
-            .fetch_optional(&*DB_POOL)
-            .await
-            .map_err(|e| e.to_string())?;
+    Ok(data.map(FsrsSettings::from).unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_fsrs_settings(user_key: &str) -> Result<FsrsSettings, String> {
+    let user_id = get_user_id(user_key, None).await?;
+    load_fsrs_settings(user_id).await
+}
+
+#[tauri::command]
+pub async fn set_fsrs_settings(
+    user_key: &str,
+    easy_threshold: f64,
+    hard_threshold: f64,
+) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&easy_threshold)
+        || !(0.0..=1.0).contains(&hard_threshold)
+        || easy_threshold >= hard_threshold
+    {
+        return Err(format!(
+            "Invalid thresholds: expected 0 <= easy ({}) < hard ({}) <= 1",
+            easy_threshold, hard_threshold
+        ));
+    }
+
+    let user_id = get_user_id(user_key, None).await?;
+    sqlx::query_file!(
+        "./queries/upsert_fsrs_settings.sql",
+        user_id,
+        easy_threshold,
+        hard_threshold
+    )
+    .execute(&*DB_POOL)
+    .await
+    .map_err(|e| format!("Failed to save FSRS settings: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn update_card_fsrs(
+    user_key: &str,
+    dictionary_id: i64,
+    word: &str,
+    is_correct: bool,
+    duration: Option<i64>,
+    round_duration: i64,
+    answer_stats_id: i64,
+) -> Result<(), String> {
+    let user_id = get_user_id(user_key, None).await?;
+    let settings = load_fsrs_settings(user_id).await?;
+    let existing_card = sqlx::query_file_as!(
+        CardFsrsRecord,
+        "./queries/get_card_fsrs.sql",
+        user_id,
+        dictionary_id,
+        word
+    )
+    .fetch_optional(&*DB_POOL)
+    .await
+    .map_err(|e| format!("Failed to fetch card: {}", e))?;
 
-        if let Some(user) = userdata {
-            Ok(user.id)
-        } else {
-            Err("User not found".to_string())
-        }
-    }
-}
+    let fsrs = settings.fsrs();
+    let review_time = Utc::now();
+    let rating = settings.rating(is_correct, duration, round_duration);
 
-#[allow(clippy::too_many_arguments)]
-#[tauri::command]
-pub async fn add_answer_stats(
-    game_stats_id: i64,
-    user_key: &str,
-    user_name: &str,
-    word: &str,
-    word_reading: &str,
-    duration: Option<i64>,
-    is_correct: bool,
-    round_index: i64,
-    font_id: i64,
-) -> Result<i64, String> {
-    struct RawData {
-        id: i64,
-    }
+    let previous_card: Card = existing_card.map(Card::from).unwrap_or_else(Card::new);
+    let card = fsrs.repeat(previous_card.clone(), review_time)[&rating]
+        .card
+        .clone();
 
-    let user_id = get_user_id(user_key, Some(user_name))
+    let mut tx = DB_POOL
+        .begin()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-    let result = query_file_as!(
-        RawData,
-        "./queries/add_answer_stats.sql",
-        game_stats_id,
-        user_id,
-        word,
-        word_reading,
-        duration,
-        is_correct,
-        round_index,
-        font_id,
+    save_card_review(
+        &mut tx,
+        CardKey {
+            user_id,
+            dictionary_id,
+            word,
+        },
+        &previous_card,
+        &card,
+        Some(rating),
+        Some(answer_stats_id),
     )
-    .fetch_one(&*DB_POOL)
-    .await
-    .map_err(|e| e.to_string())?;
+    .await?;
 
-    Ok(result.id)
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(())
 }
 
-#[tauri::command]
-pub async fn add_game_stats(
-    rounds_count: i64,
-    round_duration: i64,
-    min_frequency: i64,
-    max_frequency: Option<i64>,
-    word_part: Option<String>,
-    word_part_reading: Option<String>,
-    font_id: Option<i64>,
+struct CardKey<'a> {
+    user_id: i64,
     dictionary_id: i64,
-) -> Result<i64, String> {
-    struct RawData {
-        id: i64,
+    word: &'a str,
+}
+
+fn rating_to_i64(rating: Rating) -> i64 {
+    match rating {
+        Rating::Again => 1,
+        Rating::Hard => 2,
+        Rating::Good => 3,
+        Rating::Easy => 4,
     }
+}
 
-    let result = sqlx::query_file_as!(
-        RawData,
-        "./queries/add_game_stats.sql",
-        rounds_count,
-        round_duration,
-        min_frequency,
-        max_frequency,
-        word_part,
-        word_part_reading,
-        font_id,
-        dictionary_id
+// Resets are logged without a rating, so the history shows where the card started over
+const RESET_RATING: i64 = 0;
+
+async fn save_card_review(
+    conn: &mut SqliteConnection,
+    key: CardKey<'_>,
+    previous_card: &Card,
+    card: &Card,
+    rating: Option<Rating>,
+    answer_stats_id: Option<i64>,
+) -> Result<(), String> {
+    let previous = card_to_record(previous_card, key.word.to_string(), None);
+    let record = card_to_record(card, key.word.to_string(), None);
+
+    sqlx::query_file!(
+        "./queries/upsert_card_fsrs.sql",
+        key.user_id,
+        key.dictionary_id,
+        record.word,
+        record.due,
+        record.stability,
+        record.difficulty,
+        record.elapsed_days,
+        record.scheduled_days,
+        record.reps,
+        record.lapses,
+        record.state,
+        record.last_review
     )
-    .fetch_one(&*DB_POOL)
+    .execute(&mut *conn)
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| format!("Failed to upsert card: {}", e))?;
 
-    Ok(result.id)
+    let rating = rating.map_or(RESET_RATING, rating_to_i64);
+    sqlx::query_file!(
+        "./queries/add_review_log.sql",
+        key.user_id,
+        key.dictionary_id,
+        record.word,
+        answer_stats_id,
+        rating,
+        record.elapsed_days,
+        previous.state,
+        previous.due,
+        previous.stability,
+        previous.difficulty,
+        record.state,
+        record.due,
+        record.stability,
+        record.difficulty,
+        record.scheduled_days,
+        record.last_review
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to add review log: {}", e))?;
+
+    Ok(())
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AnswerStreaks {
-    game_id: i64,
-    length: i64,
+pub struct ReviewLogEntry {
+    id: i64,
+    answer_stats_id: Option<i64>,
+    // RESET_RATING when the card was reset
+    rating: i64,
+    elapsed_days: i64,
+    previous_state: i64,
+    previous_due: NaiveDateTime,
+    previous_stability: f64,
+    previous_difficulty: f64,
+    state: i64,
+    due: NaiveDateTime,
+    stability: f64,
+    difficulty: f64,
+    scheduled_days: i64,
+    reviewed_at: NaiveDateTime,
 }
 
 #[tauri::command]
-pub async fn get_answer_streaks(
-    min_frequency: i64,
-    max_frequency: Option<i64>,
-    count: i64,
+pub async fn get_card_history(
     user_key: &str,
     dictionary_id: i64,
-) -> Result<Vec<AnswerStreaks>, String> {
+    word: &str,
+) -> Result<Vec<ReviewLogEntry>, String> {
     let user_id = get_user_id(user_key, None).await?;
-    let data = sqlx::query_file_as!(
-        AnswerStreaks,
-        "./queries/get_answer_streaks.sql",
-        min_frequency,
-        max_frequency,
-        count,
+    let data = query_file_as!(
+        ReviewLogEntry,
+        "./queries/get_card_history.sql",
         user_id,
-        dictionary_id
+        dictionary_id,
+        word
     )
     .fetch_all(&*DB_POOL)
     .await
@@ -509,302 +2779,639 @@ pub async fn get_answer_streaks(
     Ok(data)
 }
 
-#[tauri::command]
-pub async fn get_word_parts(dictionary_id: i64) -> Result<Vec<String>, String> {
-    let data = sqlx::query_file!("./queries/get_word_parts.sql", dictionary_id)
-        .map(|row| row.word_part)
-        .fetch_all(&*DB_POOL)
-        .await
-        .map_err(|e| e.to_string())?;
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DueDay {
+    date: NaiveDate,
+    count: i64,
+}
 
-    Ok(data)
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionForecast {
+    cards_count: i64,
+    reviewed_cards_count: i64,
+    average_retrievability: Option<f64>,
+    due_per_day: Vec<DueDay>,
+    target_date: NaiveDate,
+    expected_retention: Option<f64>,
 }
 
 #[tauri::command]
-pub async fn get_word_part_readings(
-    word_part: String,
+pub async fn get_retention_forecast(
+    user_key: &str,
     dictionary_id: i64,
-) -> Result<Vec<String>, String> {
-    let data = sqlx::query_file!(
-        "./queries/get_word_part_readings.sql",
-        word_part,
+    days: i64,
+    target_date: Option<NaiveDate>,
+) -> Result<RetentionForecast, String> {
+    let user_id = get_user_id(user_key, None).await?;
+    let cards: Vec<Card> = query_file_as!(
+        CardFsrsRecord,
+        "./queries/get_cards_fsrs.sql",
+        user_id,
         dictionary_id
     )
-    .map(|row| row.word_part_reading)
     .fetch_all(&*DB_POOL)
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| format!("Failed to fetch cards: {}", e))?
+    .into_iter()
+    .map(Card::from)
+    .collect();
+
+    let now = Utc::now();
+    let today = now.date_naive();
+    let target_date = target_date.unwrap_or(today + Duration::days(days.max(0)));
+    let target_time = target_date.and_time(NaiveTime::MIN).and_utc();
+
+    let reviewed_cards: Vec<&Card> = cards
+        .iter()
+        .filter(|card| !matches!(card.state, State::New))
+        .collect();
+
+    let average = |time: DateTime<Utc>| {
+        if reviewed_cards.is_empty() {
+            None
+        } else {
+            let total: f64 = reviewed_cards
+                .iter()
+                .map(|card| fsrs::retrievability(card, time))
+                .sum();
+            Some(total / reviewed_cards.len() as f64)
+        }
+    };
 
-    Ok(data)
+    // Overdue cards land on the first day
+    let mut due_per_day: Vec<DueDay> = (0..days.max(0))
+        .map(|offset| DueDay {
+            date: today + Duration::days(offset),
+            count: 0,
+        })
+        .collect();
+    for card in &cards {
+        let offset = (card.due.date_naive() - today).num_days().max(0) as usize;
+        if let Some(day) = due_per_day.get_mut(offset) {
+            day.count += 1;
+        }
+    }
+
+    Ok(RetentionForecast {
+        cards_count: cards.len() as i64,
+        reviewed_cards_count: reviewed_cards.len() as i64,
+        average_retrievability: average(now),
+        due_per_day,
+        target_date,
+        expected_retention: average(target_time),
+    })
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GameStats {
+pub struct FsrsOptimizationResult {
+    parameters: Vec<f64>,
+    reviews_count: i64,
+    log_loss_before: f64,
+    log_loss_after: f64,
+}
+
+struct ReviewHistoryRecord {
     id: i64,
-    rounds_count: i64,
-    round_duration: i64,
-    min_frequency: i64,
-    max_frequency: Option<i64>,
-    font_id: Option<i64>,
-    font: Option<String>,
     dictionary_id: i64,
-    dictionary: String,
-    real_rounds_count: i64,
-    users_count: i64,
+    word: String,
     timestamp: NaiveDateTime,
+    is_correct: i64,
+    duration: Option<i64>,
+    round_duration: i64,
 }
 
-#[tauri::command]
-pub async fn get_all_games_stats(dictionary_id: i64) -> Result<Vec<GameStats>, String> {
-    let data = sqlx::query_file_as!(
-        GameStats,
-        "./queries/get_all_games_stats.sql",
-        dictionary_id
+// Every (dictionary, word) pair is a separate card, reviews come ordered by time
+async fn load_review_history(
+    user_id: i64,
+    settings: &FsrsSettings,
+) -> Result<Vec<((i64, String), Vec<Review>)>, String> {
+    let records = query_file_as!(
+        ReviewHistoryRecord,
+        "./queries/get_review_history.sql",
+        user_id
     )
     .fetch_all(&*DB_POOL)
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| format!("Failed to fetch review history: {}", e))?;
+
+    let mut history: Vec<((i64, String), Vec<Review>)> = Vec::new();
+    for record in records {
+        let review = Review {
+            answer_stats_id: Some(record.id),
+            timestamp: record.timestamp.and_utc(),
+            is_correct: record.is_correct != 0,
+            rating: settings.rating(
+                record.is_correct != 0,
+                record.duration,
+                record.round_duration,
+            ),
+        };
+
+        match history.last_mut() {
+            Some(((dictionary_id, word), reviews))
+                if *dictionary_id == record.dictionary_id && *word == record.word =>
+            {
+                reviews.push(review)
+            }
+            _ => history.push(((record.dictionary_id, record.word), vec![review])),
+        }
+    }
 
-    Ok(data)
+    Ok(history)
 }
 
-#[tauri::command]
-pub async fn get_game_stats(id: i64) -> Result<GameStats, String> {
-    let data = sqlx::query_file_as!(GameStats, "./queries/get_game_stats.sql", id)
-        .fetch_optional(&*DB_POOL)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if let Some(gs) = data {
-        Ok(gs)
-    } else {
-        Err(format!("Game stats not found for id: {}", id))
+fn rating_from_i64(rating: i64) -> Rating {
+    match rating {
+        1 => Rating::Again,
+        2 => Rating::Hard,
+        4 => Rating::Easy,
+        _ => Rating::Good,
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct AnswerStats {
-    id: i64,
-    game_stats_id: i64,
-    user_id: i64,
-    user: String,
-    word: String,
-    word_reading: String,
-    duration: Option<i64>,
-    is_correct: bool,
-    round_index: i64,
-    timestamp: NaiveDateTime,
-    font_id: i64,
-    font: String,
-}
-
-#[derive(sqlx::FromRow)]
-struct AnswerStatsDB {
-    id: i64,
-    game_stats_id: i64,
+// Reviews seeded from other apps, logged without an answer, ordered by word and time
+async fn load_imported_reviews(
     user_id: i64,
-    user: String,
-    word: String,
-    word_reading: String,
-    duration: Option<i64>,
-    is_correct: i64,
-    round_index: i64,
-    timestamp: NaiveDateTime,
-    font_id: i64,
-    font: String,
-}
-
-impl From<AnswerStatsDB> for AnswerStats {
-    fn from(db: AnswerStatsDB) -> Self {
-        AnswerStats {
-            id: db.id,
-            game_stats_id: db.game_stats_id,
-            user_id: db.user_id,
-            user: db.user,
-            word: db.word,
-            word_reading: db.word_reading,
-            duration: db.duration,
-            is_correct: db.is_correct != 0,
-            round_index: db.round_index,
-            timestamp: db.timestamp,
-            font_id: db.font_id,
-            font: db.font,
-        }
-    }
+    dictionary_id: i64,
+) -> Result<Vec<(String, Review)>, String> {
+    let records = sqlx::query_file!("./queries/get_imported_reviews.sql", user_id, dictionary_id)
+        .fetch_all(&*DB_POOL)
+        .await
+        .map_err(|e| format!("Failed to fetch imported reviews: {}", e))?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let rating = rating_from_i64(record.rating);
+            let review = Review {
+                answer_stats_id: None,
+                timestamp: record.reviewed_at.and_utc(),
+                is_correct: rating != Rating::Again,
+                rating,
+            };
+            (record.word, review)
+        })
+        .collect())
 }
 
 #[tauri::command]
-pub async fn get_answer_stats_by_game(game_stats_id: i64) -> Result<Vec<AnswerStats>, String> {
-    let data = sqlx::query_file_as!(
-        AnswerStatsDB,
-        "./queries/get_answer_stats_by_game.sql",
-        game_stats_id
+pub async fn optimize_fsrs_parameters(user_key: &str) -> Result<FsrsOptimizationResult, String> {
+    const MIN_REVIEWS_COUNT: usize = 50;
+
+    let user_id = get_user_id(user_key, None).await?;
+    let settings = load_fsrs_settings(user_id).await?;
+    let history: Vec<Vec<Review>> = load_review_history(user_id, &settings)
+        .await?
+        .into_iter()
+        .map(|(_, reviews)| reviews)
+        .collect();
+
+    let initial_weights = settings.weights();
+    let (log_loss_before, reviews_count) = fsrs::log_loss(&history, &initial_weights);
+    if reviews_count < MIN_REVIEWS_COUNT {
+        return Err(format!(
+            "Not enough review history: {} spaced reviews, at least {} required",
+            reviews_count, MIN_REVIEWS_COUNT
+        ));
+    }
+
+    let (parameters, log_loss_after) = tokio::task::spawn_blocking(move || {
+        let parameters = fsrs::optimize(&history, &initial_weights);
+        let (log_loss_after, _) = fsrs::log_loss(&history, &parameters);
+        (parameters, log_loss_after)
+    })
+    .await
+    .map_err(|e| format!("Optimization failed: {}", e))?;
+
+    let serialized_parameters = serde_json::to_string(&parameters)
+        .map_err(|e| format!("Failed to serialize parameters: {}", e))?;
+
+    sqlx::query_file!(
+        "./queries/save_fsrs_parameters.sql",
+        user_id,
+        settings.easy_threshold,
+        settings.hard_threshold,
+        serialized_parameters
     )
-    .fetch_all(&*DB_POOL)
+    .execute(&*DB_POOL)
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| format!("Failed to save FSRS parameters: {}", e))?;
+
+    Ok(FsrsOptimizationResult {
+        parameters,
+        reviews_count: reviews_count as i64,
+        log_loss_before,
+        log_loss_after,
+    })
+}
 
-    Ok(data.into_iter().map(AnswerStats::from).collect())
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CardChange {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardRebuildDiff {
+    word: String,
+    change: CardChange,
+    current: Option<CardFsrsRecord>,
+    rebuilt: Option<CardFsrsRecord>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardRebuildReport {
+    dry_run: bool,
+    reviews_count: i64,
+    added_count: i64,
+    removed_count: i64,
+    changed_count: i64,
+    unchanged_count: i64,
+    diffs: Vec<CardRebuildDiff>,
+}
+
+fn is_same_schedule(current: &CardFsrsRecord, rebuilt: &CardFsrsRecord) -> bool {
+    const EPSILON: f64 = 1e-9;
+
+    current.due == rebuilt.due
+        && current.last_review == rebuilt.last_review
+        && (current.stability - rebuilt.stability).abs() < EPSILON
+        && (current.difficulty - rebuilt.difficulty).abs() < EPSILON
+        && current.reps == rebuilt.reps
+        && current.lapses == rebuilt.lapses
+        && current.state == rebuilt.state
 }
 
 #[tauri::command]
-pub async fn get_all_answer_stats(dictionary_id: i64) -> Result<Vec<AnswerStats>, String> {
-    let data = sqlx::query_file_as!(
-        AnswerStatsDB,
-        "./queries/get_all_answer_stats.sql",
+pub async fn rebuild_cards_fsrs(
+    user_key: &str,
+    dictionary_id: i64,
+    dry_run: bool,
+) -> Result<CardRebuildReport, String> {
+    let user_id = get_user_id(user_key, None).await?;
+    let settings = load_fsrs_settings(user_id).await?;
+    let fsrs = settings.fsrs();
+
+    let mut history: BTreeMap<String, Vec<Review>> = BTreeMap::new();
+    for ((history_dictionary_id, word), reviews) in load_review_history(user_id, &settings).await? {
+        if history_dictionary_id == dictionary_id {
+            history.entry(word).or_default().extend(reviews);
+        }
+    }
+    // Reviews imported from Anki have no answers to replay from, they are kept as logged
+    for (word, review) in load_imported_reviews(user_id, dictionary_id).await? {
+        history.entry(word).or_default().push(review);
+    }
+    for reviews in history.values_mut() {
+        reviews.sort_by_key(|review| review.timestamp);
+    }
+
+    let mut current_cards: HashMap<String, CardFsrsRecord> = query_file_as!(
+        CardFsrsRecord,
+        "./queries/get_cards_fsrs.sql",
+        user_id,
         dictionary_id
     )
     .fetch_all(&*DB_POOL)
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| format!("Failed to fetch cards: {}", e))?
+    .into_iter()
+    .map(|record| (record.word.clone(), record))
+    .collect();
+
+    let replayed: Vec<_> = history
+        .iter()
+        .map(|(word, reviews)| (word.as_str(), reviews.as_slice(), fsrs::replay(&fsrs, reviews)))
+        .collect();
+
+    let mut report = CardRebuildReport {
+        dry_run,
+        reviews_count: history.values().map(|reviews| reviews.len() as i64).sum(),
+        added_count: 0,
+        removed_count: 0,
+        changed_count: 0,
+        unchanged_count: 0,
+        diffs: Vec::new(),
+    };
 
-    Ok(data.into_iter().map(AnswerStats::from).collect())
-}
+    for (word, _, steps) in &replayed {
+        let Some((_, card)) = steps.last() else {
+            continue;
+        };
+        let rebuilt = card_to_record(card, word.to_string(), None);
+
+        match current_cards.remove(*word) {
+            Some(current) if is_same_schedule(&current, &rebuilt) => report.unchanged_count += 1,
+            Some(current) => {
+                report.changed_count += 1;
+                report.diffs.push(CardRebuildDiff {
+                    word: word.to_string(),
+                    change: CardChange::Changed,
+                    current: Some(current),
+                    rebuilt: Some(rebuilt),
+                });
+            }
+            None => {
+                report.added_count += 1;
+                report.diffs.push(CardRebuildDiff {
+                    word: word.to_string(),
+                    change: CardChange::Added,
+                    current: None,
+                    rebuilt: Some(rebuilt),
+                });
+            }
+        }
+    }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct User {
-    id: i64,
-    key: String,
-    username: String,
-}
+    let flagged_words: HashSet<String> = sqlx::query_file_scalar!(
+        "./queries/get_flagged_cards_fsrs.sql",
+        user_id,
+        dictionary_id
+    )
+    .fetch_all(&*DB_POOL)
+    .await
+    .map_err(|e| format!("Failed to fetch cards: {}", e))?
+    .into_iter()
+    .collect();
+
+    // Cards left over have no reviews behind them, flagged ones are kept as new cards
+    let mut current_cards: Vec<CardFsrsRecord> = current_cards.into_values().collect();
+    current_cards.sort_by(|a, b| a.word.cmp(&b.word));
+    for current in current_cards {
+        if !flagged_words.contains(&current.word) {
+            report.removed_count += 1;
+            report.diffs.push(CardRebuildDiff {
+                word: current.word.clone(),
+                change: CardChange::Removed,
+                current: Some(current),
+                rebuilt: None,
+            });
+        } else if current.reps == 0 && current.state == 0 {
+            report.unchanged_count += 1;
+        } else {
+            report.changed_count += 1;
+            report.diffs.push(CardRebuildDiff {
+                word: current.word.clone(),
+                change: CardChange::Changed,
+                rebuilt: Some(card_to_record(&Card::new(), current.word.clone(), None)),
+                current: Some(current),
+            });
+        }
+    }
 
-#[tauri::command]
-pub async fn get_userdata_by_id(user_id: i64) -> Result<User, String> {
-    let data = sqlx::query_file_as!(User, "./queries/get_userdata_by_id.sql", user_id)
-        .fetch_optional(&*DB_POOL)
+    if dry_run {
+        return Ok(report);
+    }
+
+    let mut tx = DB_POOL
+        .begin()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-    if let Some(user) = data {
-        Ok(User {
-            id: user_id,
-            username: user.username,
-            key: user.key,
-        })
-    } else {
-        Err(format!("User not found with id: {}", user_id))
+    let now = Utc::now().naive_utc();
+    sqlx::query_file!(
+        "./queries/reset_cards_fsrs.sql",
+        user_id,
+        dictionary_id,
+        user_id,
+        dictionary_id,
+        now,
+        user_id,
+        dictionary_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to reset cards: {}", e))?;
+
+    for (word, reviews, steps) in &replayed {
+        for (review, (previous_card, card)) in reviews.iter().zip(steps) {
+            save_card_review(
+                &mut tx,
+                CardKey {
+                    user_id,
+                    dictionary_id,
+                    word,
+                },
+                previous_card,
+                card,
+                Some(review.rating),
+                review.answer_stats_id,
+            )
+            .await?;
+        }
     }
-}
 
-#[tauri::command]
-pub async fn get_all_users() -> Result<Vec<User>, String> {
-    let users = sqlx::query_file_as!(User, "./queries/get_all_users.sql")
-        .fetch_all(&*DB_POOL)
+    tx.commit()
         .await
-        .map_err(|e| e.to_string())?;
-    Ok(users)
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(report)
 }
 
+// Replays reviews made outside of KanjiLab, returns the numbers of seeded cards and reviews
+pub(crate) async fn seed_cards_fsrs(
+    user_id: i64,
+    dictionary_guid: &str,
+    history: &[(String, Vec<Review>)],
+) -> Result<(u64, u64), String> {
+    let settings = load_fsrs_settings(user_id).await?;
+    let fsrs = settings.fsrs();
+
+    let dictionary_id = sqlx::query_file_scalar!(
+        "./queries/get_dictionary_id_by_guid.sql",
+        dictionary_guid
+    )
+    .fetch_optional(&*DB_POOL)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Dictionary not found for guid: {}", dictionary_guid))?;
 
-#[derive(Debug)]
-struct CardFsrsRecord {
-    id: Option<i64>,
-    word: String,
-    due: NaiveDateTime,
-    stability: f64,
-    difficulty: f64,
-    elapsed_days: i64,
-    scheduled_days: i64,
-    reps: i64,
-    lapses: i64,
-    state: i64,
-    last_review: NaiveDateTime,
-}
+    // Reviews from an earlier import of the same deck are already logged
+    let imported: HashSet<(String, DateTime<Utc>)> = load_imported_reviews(user_id, dictionary_id)
+        .await?
+        .into_iter()
+        .map(|(word, review)| (word, review.timestamp))
+        .collect();
 
-impl From<CardFsrsRecord> for Card {
-    fn from(record: CardFsrsRecord) -> Self {
-        Card {
-            due: record.due.and_utc(),
-            stability: record.stability,
-            difficulty: record.difficulty,
-            elapsed_days: record.elapsed_days,
-            scheduled_days: record.scheduled_days,
-            reps: record.reps as i32,
-            lapses: record.lapses as i32,
-            state: match record.state {
-                0 => State::New,
-                1 => State::Learning,
-                2 => State::Review,
-                3 => State::Relearning,
-                _ => State::New,
-            },
-            last_review: record.last_review.and_utc(),
+    let mut tx = DB_POOL
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    let mut cards_count = 0;
+    let mut reviews_count = 0;
+    for (word, reviews) in history.iter().filter(|(_, reviews)| !reviews.is_empty()) {
+        let steps = fsrs::replay(&fsrs, reviews);
+        let mut is_updated = false;
+        for (review, (previous_card, card)) in reviews.iter().zip(&steps) {
+            if imported.contains(&(word.clone(), review.timestamp)) {
+                continue;
+            }
+            save_card_review(
+                &mut tx,
+                CardKey {
+                    user_id,
+                    dictionary_id,
+                    word,
+                },
+                previous_card,
+                card,
+                Some(review.rating),
+                review.answer_stats_id,
+            )
+            .await?;
+            reviews_count += 1;
+            is_updated = true;
+        }
+        if is_updated {
+            cards_count += 1;
         }
     }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok((cards_count, reviews_count))
 }
 
-fn card_to_record(card: &Card, word: String, id: Option<i64>) -> CardFsrsRecord {
-    CardFsrsRecord {
-        id,
-        word,
-        due: card.due.naive_utc(),
-        stability: card.stability,
-        difficulty: card.difficulty,
-        elapsed_days: card.elapsed_days,
-        scheduled_days: card.scheduled_days,
-        reps: card.reps as i64,
-        lapses: card.lapses as i64,
-        state: match card.state {
-            State::New => 0,
-            State::Learning => 1,
-            State::Review => 2,
-            State::Relearning => 3,
-        },
-        last_review: card.last_review.naive_utc(),
-    }
+async fn ensure_card_fsrs(user_id: i64, dictionary_id: i64, word: &str) -> Result<(), String> {
+    let record = card_to_record(&Card::new(), word.to_string(), None);
+
+    sqlx::query_file!(
+        "./queries/add_card_fsrs.sql",
+        user_id,
+        dictionary_id,
+        record.word,
+        record.due,
+        record.stability,
+        record.difficulty,
+        record.elapsed_days,
+        record.scheduled_days,
+        record.reps,
+        record.lapses,
+        record.state,
+        record.last_review
+    )
+    .execute(&*DB_POOL)
+    .await
+    .map_err(|e| format!("Failed to add card: {}", e))?;
+
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn update_card_fsrs(
+pub async fn set_card_suspended(
+    user_key: &str,
+    dictionary_id: i64,
     word: &str,
-    is_correct: bool,
+    is_suspended: bool,
 ) -> Result<(), String> {
+    let user_id = get_user_id(user_key, None).await?;
+    ensure_card_fsrs(user_id, dictionary_id, word).await?;
+
+    sqlx::query_file!(
+        "./queries/set_card_fsrs_suspended.sql",
+        is_suspended,
+        user_id,
+        dictionary_id,
+        word
+    )
+    .execute(&*DB_POOL)
+    .await
+    .map_err(|e| format!("Failed to suspend card: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn bury_card(user_key: &str, dictionary_id: i64, word: &str) -> Result<(), String> {
+    let user_id = get_user_id(user_key, None).await?;
+    ensure_card_fsrs(user_id, dictionary_id, word).await?;
+
+    let tomorrow = (Utc::now().date_naive() + Duration::days(1)).and_time(NaiveTime::MIN);
+    sqlx::query_file!(
+        "./queries/set_card_fsrs_buried_until.sql",
+        tomorrow,
+        user_id,
+        dictionary_id,
+        word
+    )
+    .execute(&*DB_POOL)
+    .await
+    .map_err(|e| format!("Failed to bury card: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reset_card(user_key: &str, dictionary_id: i64, word: &str) -> Result<(), String> {
+    let user_id = get_user_id(user_key, None).await?;
     let existing_card = sqlx::query_file_as!(
         CardFsrsRecord,
         "./queries/get_card_fsrs.sql",
+        user_id,
+        dictionary_id,
         word
     )
     .fetch_optional(&*DB_POOL)
     .await
     .map_err(|e| format!("Failed to fetch card: {}", e))?;
+    let previous_card: Card = existing_card.map(Card::from).unwrap_or_else(Card::new);
 
-    let fsrs = FSRS::default();
-    let review_time = Utc::now();
-    let rating = if is_correct {
-        Rating::Good
-    } else {
-        Rating::Again
-    };
+    let mut tx = DB_POOL
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
-    let card = if let Some(existing) = existing_card {
-        let card: Card = existing.into();
-        fsrs.repeat(card, review_time)[&rating].card.clone()
-    } else {
-        let card = Card::new();
-        fsrs.repeat(card, review_time)[&rating].card.clone()
-    };
+    save_card_review(
+        &mut tx,
+        CardKey {
+            user_id,
+            dictionary_id,
+            word,
+        },
+        &previous_card,
+        &Card::new(),
+        None,
+        None,
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
 
-    let record = card_to_record(&card, word.to_string(), None);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_card_note(
+    user_key: &str,
+    dictionary_id: i64,
+    word: &str,
+    note: Option<&str>,
+) -> Result<(), String> {
+    let user_id = get_user_id(user_key, None).await?;
+    ensure_card_fsrs(user_id, dictionary_id, word).await?;
 
     sqlx::query_file!(
-        "./queries/upsert_card_fsrs.sql",
-        record.word,
-        record.due,
-        record.stability,
-        record.difficulty,
-        record.elapsed_days,
-        record.scheduled_days,
-        record.reps,
-        record.lapses,
-        record.state,
-        record.last_review
+        "./queries/set_card_fsrs_note.sql",
+        note,
+        user_id,
+        dictionary_id,
+        word
     )
     .execute(&*DB_POOL)
     .await
-    .map_err(|e| format!("Failed to upsert card: {}", e))?;
+    .map_err(|e| format!("Failed to save card note: {}", e))?;
 
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,1408 @@
+use super::*;
+use crate::anki::import_anki_deck;
+use crate::dict_builder::{self, DictionaryData, WordListRecord};
+use chrono::TimeZone;
+use std::future::Future;
+use std::io::Write;
+use std::sync::Once;
+use std::time::Instant;
+use tauri::test::{MockRuntime, mock_app};
+
+static RUNTIME: LazyLock<tokio::runtime::Runtime> =
+    LazyLock::new(|| tokio::runtime::Runtime::new().unwrap());
+// Tests share one database, so they run one at a time
+static DB_LOCK: Mutex<()> = Mutex::new(());
+static DB_INIT: Once = Once::new();
+
+fn run<F: Future>(future: F) -> F::Output {
+    let _guard = DB_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    DB_INIT.call_once(|| {
+        let path = main_db_path();
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+        RUNTIME.block_on(init_db());
+    });
+    RUNTIME.block_on(future)
+}
+
+fn app() -> AppHandle<MockRuntime> {
+    mock_app().handle().clone()
+}
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("kanjilab_test_{}_{}.db", std::process::id(), name))
+        .into_os_string()
+        .into_string()
+        .unwrap()
+}
+
+// Rows are word, readings and meanings in word list format
+fn word_list(rows: &[(&str, &str, &str)]) -> DictionaryData {
+    let mut data = DictionaryData::default();
+    for (word, reading, meanings) in rows {
+        let record = WordListRecord {
+            word: word.to_string(),
+            reading: reading.to_string(),
+            frequency: None,
+            meanings: Some(meanings.to_string()),
+            furigana: None,
+        };
+        assert!(dict_builder::add_word_list_record(&mut data, &record));
+    }
+    data
+}
+
+async fn dictionary_id(name: &str) -> i64 {
+    sqlx::query_scalar("SELECT id FROM dictionary WHERE name = $1 AND is_exist")
+        .bind(name)
+        .fetch_one(&*DB_POOL)
+        .await
+        .unwrap()
+}
+
+async fn import_word_list(name: &str, rows: &[(&str, &str, &str)]) -> i64 {
+    let guid = Uuid::new_v4().to_string();
+    dict_builder::import_dictionary_data(app(), &word_list(rows), &guid, name, None)
+        .await
+        .unwrap();
+    dictionary_id(name).await
+}
+
+// Words with their readings as `word:reading`, sorted
+async fn dictionary_readings(conn: &mut SqliteConnection, schema: &str) -> Vec<String> {
+    sqlx::query_scalar(&format!(
+        "SELECT w.word || ':' || wr.word_reading FROM {schema}.word w
+            JOIN {schema}.word_reading wr ON wr.word_id = w.id ORDER BY 1"
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .unwrap()
+}
+
+const WORDS: [(&str, &str, &str); 4] = [
+    ("日本", "にほん;にっぽん", "Japan"),
+    ("学生", "がくせい", "student"),
+    ("先生", "せんせい", "teacher;master"),
+    ("水", "みず", "water"),
+];
+
+#[test]
+fn migrations_are_applied() {
+    run(async {
+        let applied: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success")
+                .fetch_one(&*DB_POOL)
+                .await
+                .unwrap();
+        assert_eq!(
+            applied as usize,
+            sqlx::migrate!("./migrations/main").iter().count()
+        );
+
+        for table in [
+            "dictionary",
+            "word",
+            "card_fsrs",
+            "review_log",
+            "word_search",
+            "kanji",
+        ] {
+            let exists: bool =
+                sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = $1)")
+                    .bind(table)
+                    .fetch_one(&*DB_POOL)
+                    .await
+                    .unwrap();
+            assert!(exists, "missing table {}", table);
+        }
+    });
+}
+
+#[test]
+fn kanji_jlpt_filter_maps_n_levels_to_old_levels() {
+    let old_level = |level| {
+        WordFilter {
+            min_kanji_jlpt_n: level,
+            ..Default::default()
+        }
+        .min_kanji_old_jlpt()
+    };
+    assert_eq!(old_level(None), None);
+    let levels: Vec<Option<i64>> = (1..=5).map(|level| old_level(Some(level))).collect();
+    assert_eq!(levels, [Some(1), Some(2), Some(2), Some(3), Some(4)]);
+}
+
+#[test]
+fn rating_follows_thresholds() {
+    let settings = FsrsSettings::default();
+
+    assert_eq!(settings.rating(false, Some(1000), 10000), Rating::Again);
+    assert_eq!(settings.rating(true, None, 10000), Rating::Good);
+    assert_eq!(settings.rating(true, Some(1000), 0), Rating::Good);
+    assert_eq!(settings.rating(true, Some(1000), 10000), Rating::Easy);
+    assert_eq!(settings.rating(true, Some(2500), 10000), Rating::Easy);
+    assert_eq!(settings.rating(true, Some(5000), 10000), Rating::Good);
+    assert_eq!(settings.rating(true, Some(7500), 10000), Rating::Hard);
+    assert_eq!(settings.rating(true, Some(12000), 10000), Rating::Hard);
+}
+
+#[test]
+fn invalid_thresholds_are_rejected() {
+    run(async {
+        assert!(set_fsrs_settings("thresholds", 0.8, 0.2).await.is_err());
+        assert!(set_fsrs_settings("thresholds", -0.1, 0.5).await.is_err());
+        assert!(set_fsrs_settings("thresholds", 0.5, 0.5).await.is_err());
+
+        get_user_id("thresholds", Some("Thresholds")).await.unwrap();
+        set_fsrs_settings("thresholds", 0.1, 0.9).await.unwrap();
+        let settings = get_fsrs_settings("thresholds").await.unwrap();
+        assert_eq!(settings.easy_threshold, 0.1);
+        assert_eq!(settings.hard_threshold, 0.9);
+    });
+}
+
+#[test]
+fn validation_reports_broken_files() {
+    run(async {
+        let path = temp_path("validation_empty");
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true);
+        SqliteConnection::connect_with(&options)
+            .await
+            .unwrap()
+            .close()
+            .await
+            .unwrap();
+        let report = validate_dictionary(&path).await.unwrap();
+        assert!(!report.is_valid);
+        assert_eq!(report.errors[0].code, "missingTables");
+
+        let path = temp_path("validation_broken");
+        create_dictionary_database(&path)
+            .await
+            .unwrap()
+            .close()
+            .await;
+        let mut conn = SqliteConnection::connect_with(
+            &SqliteConnectOptions::new()
+                .filename(&path)
+                .foreign_keys(false),
+        )
+        .await
+        .unwrap();
+        for sql in [
+            "INSERT INTO dictionary_info (guid, name) VALUES ('validation', 'Validation')",
+            "INSERT INTO word (id, word, meanings) VALUES (1, '日本', ''), (2, '学生', 'student')",
+            "INSERT INTO word_reading (id, word_id, word_reading) VALUES (1, 1, 'にほん'), (2, 3, 'みず')",
+        ] {
+            sqlx::query(sql).execute(&mut conn).await.unwrap();
+        }
+        conn.close().await.unwrap();
+
+        let report = validate_dictionary(&path).await.unwrap();
+        let errors: Vec<&str> = report
+            .errors
+            .iter()
+            .map(|issue| issue.code.as_str())
+            .collect();
+        let warnings: Vec<&str> = report
+            .warnings
+            .iter()
+            .map(|issue| issue.code.as_str())
+            .collect();
+        assert!(!report.is_valid);
+        assert_eq!(errors, ["danglingReadings"]);
+        assert_eq!(warnings, ["wordsWithoutReadings", "emptyMeanings"]);
+
+        assert!(import_dictionary(app(), &path).await.is_err());
+        let _ = std::fs::remove_file(temp_path("validation_empty"));
+        let _ = std::fs::remove_file(&path);
+    });
+}
+
+#[test]
+fn export_round_trip_keeps_dictionary() {
+    run(async {
+        let id = import_word_list("Export round trip", &WORDS).await;
+        let path = temp_path("export");
+        export_dictionary(id, &path).await.unwrap();
+
+        let report = validate_dictionary(&path).await.unwrap();
+        assert!(report.is_valid);
+        assert!(report.warnings.is_empty());
+
+        let mut conn = DB_POOL.acquire().await.unwrap();
+        sqlx::query("ATTACH DATABASE ? AS exported")
+            .bind(&path)
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        let exported = dictionary_readings(&mut conn, "exported").await;
+        let installed: Vec<String> = sqlx::query_scalar(
+            "SELECT w.word || ':' || wr.word_reading FROM word w
+                JOIN word_reading wr ON wr.word_id = w.id WHERE w.dictionary_id = $1 ORDER BY 1",
+        )
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        sqlx::query("DETACH DATABASE exported")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        drop(conn);
+        assert_eq!(exported.len(), 5);
+        assert_eq!(exported, installed);
+
+        // The exported file has the same guid, so importing it again changes nothing
+        let summary = import_dictionary(app(), &path).await.unwrap();
+        assert_eq!(
+            (
+                summary.words_added,
+                summary.words_removed,
+                summary.words_changed
+            ),
+            (0, 0, 0)
+        );
+        assert_eq!(
+            (
+                summary.readings_added,
+                summary.readings_removed,
+                summary.readings_changed
+            ),
+            (0, 0, 0)
+        );
+        assert_eq!(dictionary_id("Export round trip").await, id);
+        let _ = std::fs::remove_file(&path);
+    });
+}
+
+#[test]
+fn reading_flags_survive_import_and_export() {
+    run(async {
+        let id = import_word_list("Reading flags", &WORDS).await;
+        let path = temp_path("reading_flags");
+        export_dictionary(id, &path).await.unwrap();
+
+        let mut file = SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path))
+            .await
+            .unwrap();
+        for sql in [
+            "UPDATE word_reading SET is_common = true, pitch_accent = '2' WHERE word_reading = 'にほん'",
+            "UPDATE word_reading SET is_rare = true, pitch_accent = '3,0' WHERE word_reading = 'にっぽん'",
+            "UPDATE word_reading SET is_irregular = true WHERE word_reading = 'みず'",
+        ] {
+            sqlx::query(sql).execute(&mut file).await.unwrap();
+        }
+        file.close().await.unwrap();
+
+        let summary = import_dictionary(app(), &path).await.unwrap();
+        assert_eq!(summary.readings_changed, 3);
+        assert_eq!((summary.readings_added, summary.readings_removed), (0, 0));
+
+        let word = get_word_details("日本", id).await.unwrap().unwrap();
+        let readings: Vec<(&str, bool, bool, bool, &[i64])> = word
+            .readings
+            .iter()
+            .map(|reading| {
+                (
+                    reading.reading.as_str(),
+                    reading.is_common,
+                    reading.is_rare,
+                    reading.is_irregular,
+                    reading.pitch_accents.as_slice(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            readings,
+            [
+                ("にっぽん", false, true, false, &[3, 0][..]),
+                ("にほん", true, false, false, &[2][..])
+            ]
+        );
+        let water = get_word_details("水", id).await.unwrap().unwrap();
+        assert!(water.readings[0].is_irregular);
+        assert!(water.readings[0].pitch_accents.is_empty());
+
+        // Exporting again keeps the flags
+        export_dictionary(id, &path).await.unwrap();
+        let mut file = SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path))
+            .await
+            .unwrap();
+        let flagged: Vec<(String, Option<String>)> = sqlx::query_as(
+            "SELECT word_reading, pitch_accent FROM word_reading
+                WHERE is_common OR is_rare OR is_irregular ORDER BY word_reading",
+        )
+        .fetch_all(&mut file)
+        .await
+        .unwrap();
+        // Files written before the flags were added leave the installed flags alone
+        for column in ["is_common", "is_rare", "is_irregular", "pitch_accent"] {
+            sqlx::query(&format!("ALTER TABLE word_reading DROP COLUMN {}", column))
+                .execute(&mut file)
+                .await
+                .unwrap();
+        }
+        file.close().await.unwrap();
+        let summary = import_dictionary(app(), &path).await.unwrap();
+        assert_eq!(summary.readings_changed, 0);
+        let word = get_word_details("日本", id).await.unwrap().unwrap();
+        assert!(
+            word.readings
+                .iter()
+                .all(|reading| !reading.pitch_accents.is_empty())
+        );
+
+        assert_eq!(
+            flagged,
+            [
+                ("にっぽん".to_string(), Some("3,0".to_string())),
+                ("にほん".to_string(), Some("2".to_string())),
+                ("みず".to_string(), None)
+            ]
+        );
+        let _ = std::fs::remove_file(&path);
+    });
+}
+
+#[test]
+fn import_handles_sparse_rowids() {
+    run(async {
+        let id = import_word_list("Sparse source", &WORDS).await;
+        let path = temp_path("sparse");
+        export_dictionary(id, &path).await.unwrap();
+
+        // Move one word far past the others, like a file edited after it was built
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .foreign_keys(false);
+        let mut file = SqliteConnection::connect_with(&options).await.unwrap();
+        for sql in [
+            "UPDATE dictionary_info SET guid = 'sparse-rowids', name = 'Sparse'",
+            "UPDATE word SET id = id + (1 << 40) WHERE word = '水'",
+            "UPDATE word_reading SET word_id = word_id + (1 << 40), id = id + (1 << 40)
+                WHERE word_id = (SELECT id - (1 << 40) FROM word WHERE word = '水')",
+            "UPDATE word_reading_word_part_reading SET word_reading_id = word_reading_id + (1 << 40)
+                WHERE word_reading_id + (1 << 40) IN (SELECT id FROM word_reading)",
+        ] {
+            sqlx::query(sql).execute(&mut file).await.unwrap();
+        }
+        file.close().await.unwrap();
+
+        let summary = import_dictionary(app(), &path).await.unwrap();
+        assert_eq!(summary.words_added, 4);
+        assert_eq!(summary.readings_added, 5);
+
+        let mut conn = DB_POOL.acquire().await.unwrap();
+        let installed: Vec<String> = sqlx::query_scalar(
+            "SELECT w.word || ':' || wr.word_reading FROM word w
+                JOIN word_reading wr ON wr.word_id = w.id WHERE w.dictionary_id = $1 ORDER BY 1",
+        )
+        .bind(dictionary_id("Sparse").await)
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(installed.len(), 5);
+        assert!(installed.contains(&"水:みず".to_string()));
+        let _ = std::fs::remove_file(&path);
+    });
+}
+
+#[test]
+fn word_list_reimport_updates_chosen_dictionary() {
+    run(async {
+        let path = temp_path("word_list");
+        std::fs::write(&path, "word,reading\n日本,にほん\n水,みず\n").unwrap();
+        let report = dict_builder::import_word_list(app(), path.clone(), "List", None, None)
+            .await
+            .unwrap();
+        let id = dictionary_id("List").await;
+        assert_eq!(report.summary.words_added, 2);
+
+        std::fs::write(&path, "word,reading\n日本,にほん;にっぽん\n火,ひ\n").unwrap();
+        let report = dict_builder::import_word_list(app(), path.clone(), "List", None, Some(id))
+            .await
+            .unwrap();
+        assert_eq!(
+            (report.summary.words_added, report.summary.words_removed),
+            (1, 1)
+        );
+        assert_eq!(
+            (
+                report.summary.readings_added,
+                report.summary.readings_removed
+            ),
+            (2, 1)
+        );
+        assert_eq!(dictionary_id("List").await, id);
+
+        let mut conn = DB_POOL.acquire().await.unwrap();
+        let dictionaries: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM dictionary WHERE name = 'List'")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(dictionaries, 1);
+
+        // Without a target dictionary the list is added as a new one
+        dict_builder::import_word_list(app(), path.clone(), "List copy", None, None)
+            .await
+            .unwrap();
+        assert_ne!(dictionary_id("List copy").await, id);
+        let _ = std::fs::remove_file(&path);
+    });
+}
+
+// Ids of words and readings as `word:reading` keys
+async fn reading_ids(dictionary_id: i64) -> HashMap<String, (i64, i64)> {
+    sqlx::query_as::<_, (String, i64, i64)>(
+        "SELECT w.word || ':' || wr.word_reading, w.id, wr.id FROM word w
+            JOIN word_reading wr ON wr.word_id = w.id WHERE w.dictionary_id = $1",
+    )
+    .bind(dictionary_id)
+    .fetch_all(&*DB_POOL)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|(key, word_id, reading_id)| (key, (word_id, reading_id)))
+    .collect()
+}
+
+#[test]
+fn reimport_keeps_ids_of_unchanged_rows() {
+    run(async {
+        let guid = Uuid::new_v4().to_string();
+        dict_builder::import_dictionary_data(app(), &word_list(&WORDS), &guid, "Ids", None)
+            .await
+            .unwrap();
+        let id = dictionary_id("Ids").await;
+        let before = reading_ids(id).await;
+
+        let rows = [
+            ("日本", "にほん", "Japan"),
+            ("学生", "がくせい", "pupil"),
+            ("水", "みず", "water"),
+            ("火", "ひ", "fire"),
+        ];
+        let (_, summary) =
+            dict_builder::import_dictionary_data(app(), &word_list(&rows), &guid, "Ids", None)
+                .await
+                .unwrap();
+        assert_eq!(
+            (
+                summary.words_added,
+                summary.words_removed,
+                summary.words_changed
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!((summary.readings_added, summary.readings_removed), (1, 2));
+        assert!(summary.warnings.is_empty());
+        assert_eq!(dictionary_id("Ids").await, id);
+
+        let after = reading_ids(id).await;
+        for key in ["日本:にほん", "学生:がくせい", "水:みず"] {
+            assert_eq!(after[key], before[key], "{} changed ids", key);
+        }
+        assert!(!after.contains_key("日本:にっぽん"));
+        assert!(!after.contains_key("先生:せんせい"));
+        assert!(after.contains_key("火:ひ"));
+    });
+}
+
+const NOTE_TYPE_ID: i64 = 1342697561419;
+
+// A legacy .apkg whose notes all share one word and reading note type
+async fn anki_deck(name: &str, notes: &[(&str, &str)]) -> String {
+    let collection_path = temp_path(&format!("anki_{}", name));
+    let options = SqliteConnectOptions::new()
+        .filename(&collection_path)
+        .create_if_missing(true);
+    let mut conn = SqliteConnection::connect_with(&options).await.unwrap();
+    let models = serde_json::json!({
+        NOTE_TYPE_ID.to_string(): {
+            "name": "Japanese",
+            "flds": [{ "name": "Word", "ord": 0 }, { "name": "Reading", "ord": 1 }],
+        }
+    });
+    sqlx::query("CREATE TABLE col (models TEXT NOT NULL)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    sqlx::query(
+        "CREATE TABLE notes (id INTEGER PRIMARY KEY, mid INTEGER NOT NULL, flds TEXT NOT NULL)",
+    )
+    .execute(&mut conn)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO col (models) VALUES ($1)")
+        .bind(models.to_string())
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    for (word, reading) in notes {
+        sqlx::query("INSERT INTO notes (mid, flds) VALUES ($1, $2)")
+            .bind(NOTE_TYPE_ID)
+            .bind(format!("{}\x1f{}", word, reading))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+    }
+    conn.close().await.unwrap();
+
+    let deck_path = collection_path.replace(".db", ".apkg");
+    let mut archive = zip::ZipWriter::new(std::fs::File::create(&deck_path).unwrap());
+    archive
+        .start_file("collection.anki2", zip::write::SimpleFileOptions::default())
+        .unwrap();
+    archive
+        .write_all(&std::fs::read(&collection_path).unwrap())
+        .unwrap();
+    archive.finish().unwrap();
+    deck_path
+}
+
+async fn import_deck(path: &str, name: &str, dictionary_id: Option<i64>) -> i64 {
+    import_anki_deck(
+        app(),
+        path,
+        name,
+        NOTE_TYPE_ID,
+        0,
+        1,
+        None,
+        None,
+        dictionary_id,
+    )
+    .await
+    .unwrap();
+    self::dictionary_id(name).await
+}
+
+async fn dictionary_words(dictionary_id: i64) -> Vec<String> {
+    let mut words: Vec<String> = reading_ids(dictionary_id).await.into_keys().collect();
+    words.sort();
+    words
+}
+
+#[test]
+fn decks_sharing_a_note_type_get_their_own_dictionaries() {
+    run(async {
+        let first = anki_deck("first", &[("日本", "にほん"), ("水", "みず")]).await;
+        let second = anki_deck("second", &[("学生", "がくせい")]).await;
+        let first_id = import_deck(&first, "Anki first", None).await;
+        let second_id = import_deck(&second, "Anki second", None).await;
+        assert_ne!(first_id, second_id);
+        assert_eq!(dictionary_words(first_id).await, ["日本:にほん", "水:みず"]);
+        assert_eq!(dictionary_words(second_id).await, ["学生:がくせい"]);
+
+        // Only the dictionary chosen for the update changes
+        let updated = anki_deck("first_updated", &[("日本", "にほん"), ("火", "ひ")]).await;
+        assert_eq!(
+            import_deck(&updated, "Anki first", Some(first_id)).await,
+            first_id
+        );
+        assert_eq!(dictionary_words(first_id).await, ["日本:にほん", "火:ひ"]);
+        assert_eq!(dictionary_words(second_id).await, ["学生:がくせい"]);
+    });
+}
+
+#[test]
+fn purge_requires_listed_token() {
+    run(async {
+        let id = import_word_list("Purge", &WORDS).await;
+        delete_dictionary(id).await.unwrap();
+
+        assert!(purge_dictionary(id, "not a token").await.is_err());
+
+        let deleted = get_deleted_dictionaries().await.unwrap();
+        let token = deleted
+            .iter()
+            .find(|dictionary| dictionary.id == id)
+            .map(|dictionary| dictionary.purge_token.clone())
+            .unwrap();
+        // Listing again hands out new tokens
+        let deleted = get_deleted_dictionaries().await.unwrap();
+        assert!(purge_dictionary(id, &token).await.is_err());
+
+        let token = &deleted
+            .iter()
+            .find(|dictionary| dictionary.id == id)
+            .unwrap()
+            .purge_token;
+        purge_dictionary(id, token).await.unwrap();
+        assert!(purge_dictionary(id, token).await.is_err());
+
+        let words_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM word WHERE dictionary_id = $1")
+                .bind(id)
+                .fetch_one(&*DB_POOL)
+                .await
+                .unwrap();
+        assert_eq!(words_count, 0);
+        assert!(
+            get_dictionaries()
+                .await
+                .unwrap()
+                .iter()
+                .all(|dictionary| dictionary.id != id)
+        );
+    });
+}
+
+#[test]
+fn search_matches_words_readings_and_meanings() {
+    run(async {
+        let id = import_word_list("Search", &WORDS).await;
+        let found = |query: &'static str| async move {
+            let page = search_words(query, 0, 10, 0, id).await.unwrap();
+            let words: Vec<String> = page.words.into_iter().map(|word| word.word).collect();
+            assert_eq!(page.total_count as usize, words.len());
+            words
+        };
+
+        assert_eq!(found("日本").await, ["日本"]);
+        assert_eq!(found("がくせい").await, ["学生"]);
+        assert_eq!(found("gakusei").await, ["学生"]);
+        assert_eq!(found("sensei").await, ["先生"]);
+        assert_eq!(found("teacher").await, ["先生"]);
+        assert_eq!(found("みず").await, ["水"]);
+        assert_eq!(found("生").await, ["学生", "先生"]);
+        assert!(found("nothing").await.is_empty());
+        assert!(found("  ").await.is_empty());
+    });
+}
+
+#[test]
+fn user_lookup_errors_are_not_treated_as_missing_users() {
+    run(async {
+        let id = import_word_list("User lookup", &WORDS).await;
+        assert_eq!(find_user_id("user lookup").await.unwrap(), None);
+        let words = get_due_words(10, "user lookup", 10, WordFilter::default(), 0, id)
+            .await
+            .unwrap();
+        assert_eq!(words.len(), WORDS.len());
+
+        let user_id = get_user_id("user lookup", Some("User lookup"))
+            .await
+            .unwrap();
+        assert_eq!(find_user_id("user lookup").await.unwrap(), Some(user_id));
+
+        sqlx::query("ALTER TABLE user RENAME TO user_hidden")
+            .execute(&*DB_POOL)
+            .await
+            .unwrap();
+        let due_words = get_due_words(10, "user lookup", 10, WordFilter::default(), 0, id).await;
+        let words = get_words(
+            10,
+            WordFilter::default(),
+            WordSampling {
+                missed_bias: 1.0,
+                ..Default::default()
+            },
+            None,
+            None,
+            Some("user lookup"),
+            0,
+            id,
+        )
+        .await;
+        sqlx::query("ALTER TABLE user_hidden RENAME TO user")
+            .execute(&*DB_POOL)
+            .await
+            .unwrap();
+        assert!(due_words.is_err());
+        assert!(words.is_err());
+    });
+}
+
+#[test]
+fn due_words_follow_the_word_filter() {
+    run(async {
+        let id = import_word_list("Due filter", &WORDS).await;
+        let game_id = add_game_stats(
+            2,
+            10000,
+            WordFilter::default(),
+            WordSampling {
+                rarity_bias: 1.0,
+                ..Default::default()
+            },
+            None,
+            true,
+            None,
+            id,
+        )
+        .await
+        .unwrap();
+        let game = get_game_stats(game_id).await.unwrap();
+        assert!(game.is_review_mode);
+        assert_eq!(game.word_sampling, None);
+
+        let font_id = get_font_id("Due filter font").await.unwrap();
+        for (round_index, word) in ["学生", "先生"].into_iter().enumerate() {
+            let answer_stats_id = add_answer_stats(
+                game_id,
+                "due filter",
+                "Due filter",
+                word,
+                "",
+                Some(3000),
+                false,
+                round_index as i64,
+                font_id,
+            )
+            .await
+            .unwrap();
+            update_card_fsrs(
+                "due filter",
+                id,
+                word,
+                false,
+                Some(3000),
+                10000,
+                answer_stats_id,
+            )
+            .await
+            .unwrap();
+        }
+        sqlx::query("UPDATE card_fsrs SET due = '2000-01-01 00:00:00' WHERE dictionary_id = $1")
+            .bind(id)
+            .execute(&*DB_POOL)
+            .await
+            .unwrap();
+
+        let due = |new_words_limit, filter| async move {
+            get_due_words(10, "due filter", new_words_limit, filter, 0, id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|word| word.word)
+                .collect::<Vec<String>>()
+        };
+        let mut all = due(0, WordFilter::default()).await;
+        all.sort();
+        assert_eq!(all, ["先生", "学生"]);
+        let excluded = WordFilter {
+            exclude_words: vec!["先生".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(due(0, excluded).await, ["学生"]);
+
+        // New words fill up after the due cards and are filtered as well
+        let short = WordFilter {
+            max_word_length: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(due(10, short).await, ["水"]);
+    });
+}
+
+#[test]
+fn reset_is_logged_between_reviews() {
+    run(async {
+        let id = import_word_list("Reset", &WORDS).await;
+        let user_id = get_user_id("reset", Some("Reset")).await.unwrap();
+        let font_id = get_font_id("Reset font").await.unwrap();
+        let game_id = add_game_stats(
+            2,
+            10000,
+            WordFilter::default(),
+            WordSampling::default(),
+            None,
+            false,
+            Some(font_id),
+            id,
+        )
+        .await
+        .unwrap();
+        let answer = |round_index, is_correct| async move {
+            let answer_stats_id = add_answer_stats(
+                game_id,
+                "reset",
+                "Reset",
+                "学生",
+                "",
+                Some(3000),
+                is_correct,
+                round_index,
+                font_id,
+            )
+            .await
+            .unwrap();
+            update_card_fsrs(
+                "reset",
+                id,
+                "学生",
+                is_correct,
+                Some(3000),
+                10000,
+                answer_stats_id,
+            )
+            .await
+            .unwrap();
+        };
+
+        answer(0, true).await;
+        reset_card("reset", id, "学生").await.unwrap();
+        answer(1, false).await;
+
+        let history = get_card_history("reset", id, "学生").await.unwrap();
+        assert_eq!(history.len(), 3);
+        assert_ne!(history[0].rating, RESET_RATING);
+        assert_eq!(history[1].rating, RESET_RATING);
+        assert_eq!(history[1].answer_stats_id, None);
+        assert_eq!(history[1].state, 0);
+        assert_eq!(history[2].rating, 1);
+        // Every entry starts where the previous one left the card
+        for pair in history.windows(2) {
+            assert_eq!(pair[1].previous_state, pair[0].state);
+            assert_eq!(pair[1].previous_due, pair[0].due);
+            assert_eq!(pair[1].previous_stability, pair[0].stability);
+            assert_eq!(pair[1].previous_difficulty, pair[0].difficulty);
+        }
+        assert!(load_imported_reviews(user_id, id).await.unwrap().is_empty());
+    });
+}
+
+fn imported_review(day: u32, rating: Rating) -> Review {
+    Review {
+        answer_stats_id: None,
+        timestamp: Utc.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap(),
+        is_correct: rating != Rating::Again,
+        rating,
+    }
+}
+
+async fn review_log_counts(user_id: i64, dictionary_id: i64) -> (i64, i64) {
+    sqlx::query_as(
+        "SELECT COUNT(*), COUNT(*) FILTER (WHERE answer_stats_id IS NULL) FROM review_log
+            WHERE user_id = $1 AND dictionary_id = $2",
+    )
+    .bind(user_id)
+    .bind(dictionary_id)
+    .fetch_one(&*DB_POOL)
+    .await
+    .unwrap()
+}
+
+fn report_counts(report: &CardRebuildReport) -> (i64, i64, i64, i64, i64) {
+    (
+        report.reviews_count,
+        report.added_count,
+        report.removed_count,
+        report.changed_count,
+        report.unchanged_count,
+    )
+}
+
+#[test]
+fn rebuild_keeps_imported_reviews_and_flagged_cards() {
+    run(async {
+        let id = import_word_list("Rebuild", &WORDS).await;
+        let guid: String = sqlx::query_scalar("SELECT guid FROM dictionary WHERE id = $1")
+            .bind(id)
+            .fetch_one(&*DB_POOL)
+            .await
+            .unwrap();
+        let user_id = get_user_id("rebuild", Some("Rebuild")).await.unwrap();
+        let font_id = get_font_id("Rebuild font").await.unwrap();
+        let game_id = add_game_stats(
+            2,
+            10000,
+            WordFilter::default(),
+            WordSampling::default(),
+            None,
+            false,
+            Some(font_id),
+            id,
+        )
+        .await
+        .unwrap();
+        for (round_index, (word, is_correct)) in
+            [("学生", true), ("先生", false)].into_iter().enumerate()
+        {
+            let answer_stats_id = add_answer_stats(
+                game_id,
+                "rebuild",
+                "Rebuild",
+                word,
+                "",
+                Some(3000),
+                is_correct,
+                round_index as i64,
+                font_id,
+            )
+            .await
+            .unwrap();
+            update_card_fsrs(
+                "rebuild",
+                id,
+                word,
+                is_correct,
+                Some(3000),
+                10000,
+                answer_stats_id,
+            )
+            .await
+            .unwrap();
+        }
+        let imported = [(
+            "日本".to_string(),
+            vec![
+                imported_review(1, Rating::Good),
+                imported_review(4, Rating::Again),
+                imported_review(9, Rating::Good),
+            ],
+        )];
+        assert_eq!(
+            seed_cards_fsrs(user_id, &guid, &imported).await.unwrap(),
+            (1, 3)
+        );
+        // Importing the same deck again logs nothing twice
+        assert_eq!(
+            seed_cards_fsrs(user_id, &guid, &imported).await.unwrap(),
+            (0, 0)
+        );
+        set_card_suspended("rebuild", id, "水", true).await.unwrap();
+
+        let logs_before = review_log_counts(user_id, id).await;
+        assert_eq!(logs_before, (5, 3));
+
+        let dry_run = rebuild_cards_fsrs("rebuild", id, true).await.unwrap();
+        assert_eq!(review_log_counts(user_id, id).await, logs_before);
+        assert_eq!(dry_run.reviews_count, 5);
+        assert_eq!((dry_run.added_count, dry_run.removed_count), (0, 0));
+        assert_eq!(dry_run.changed_count + dry_run.unchanged_count, 4);
+        // Seeding replayed the same reviews, so the imported card is already up to date
+        assert!(dry_run.diffs.iter().all(|diff| diff.word != "日本"));
+
+        let applied = rebuild_cards_fsrs("rebuild", id, false).await.unwrap();
+        assert_eq!(report_counts(&applied), report_counts(&dry_run));
+        assert_eq!(review_log_counts(user_id, id).await, logs_before);
+
+        let cards: Vec<(String, i64, bool)> = sqlx::query_as(
+            "SELECT word, reps, is_suspended FROM card_fsrs
+                WHERE user_id = $1 AND dictionary_id = $2 ORDER BY word",
+        )
+        .bind(user_id)
+        .bind(id)
+        .fetch_all(&*DB_POOL)
+        .await
+        .unwrap();
+        assert_eq!(
+            cards,
+            [
+                ("先生".to_string(), 1, false),
+                ("学生".to_string(), 1, false),
+                ("日本".to_string(), 3, false),
+                ("水".to_string(), 0, true)
+            ]
+        );
+
+        let again = rebuild_cards_fsrs("rebuild", id, true).await.unwrap();
+        assert_eq!(report_counts(&again), (5, 0, 0, 0, 4));
+    });
+}
+
+// Rows are word, furigana and frequency, readings and parts come from the furigana
+fn furigana_list(rows: &[(String, String, Option<i64>)]) -> DictionaryData {
+    let mut data = DictionaryData::default();
+    for (word, furigana, frequency) in rows {
+        let record = WordListRecord {
+            word: word.clone(),
+            reading: String::new(),
+            frequency: *frequency,
+            meanings: None,
+            furigana: Some(furigana.clone()),
+        };
+        assert!(dict_builder::add_word_list_record(&mut data, &record));
+    }
+    data
+}
+
+async fn import_furigana_list(name: &str, rows: &[(String, String, Option<i64>)]) -> i64 {
+    let guid = Uuid::new_v4().to_string();
+    dict_builder::import_dictionary_data(app(), &furigana_list(rows), &guid, name, None)
+        .await
+        .unwrap();
+    dictionary_id(name).await
+}
+
+// The per-reading query get_words ran before the parts were batched
+async fn legacy_reading_parts(
+    reading_id: i64,
+    examples_count: i64,
+    dictionary_id: i64,
+) -> Vec<WordPartInfo> {
+    sqlx::query_as::<_, (i64, String, String, String, String, String)>(include_str!(
+        "fixtures/get_word_parts_examples.sql"
+    ))
+    .bind(reading_id)
+    .bind(examples_count)
+    .bind(dictionary_id)
+    .bind(dictionary_id)
+    .fetch_all(&*DB_POOL)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|(_, part, part_reading, words, frequencies, readings)| {
+        word_part_info(part, part_reading, &words, &frequencies, &readings)
+    })
+    .collect()
+}
+
+// Parts of every reading, batched and one query per reading
+async fn batched_and_legacy_parts(
+    reading_ids: &[i64],
+    examples_count: i64,
+    dictionary_id: i64,
+) -> (serde_json::Value, serde_json::Value) {
+    let mut batched = get_readings_parts(reading_ids, examples_count, dictionary_id)
+        .await
+        .unwrap();
+    let mut batched_parts = Vec::new();
+    let mut legacy_parts = Vec::new();
+    for reading_id in reading_ids {
+        batched_parts.push(batched.remove(reading_id).unwrap_or_default());
+        legacy_parts.push(legacy_reading_parts(*reading_id, examples_count, dictionary_id).await);
+    }
+    (
+        serde_json::to_value(batched_parts).unwrap(),
+        serde_json::to_value(legacy_parts).unwrap(),
+    )
+}
+
+#[test]
+fn batched_parts_match_per_reading_query() {
+    run(async {
+        let rows: Vec<(String, String, Option<i64>)> = [
+            ("日本", "日[に]本[ほん]", Some(10)),
+            ("本日", "本[ほん]日[じつ]", Some(30)),
+            ("日曜", "日[にち]曜[よう]", None),
+            ("毎日", "毎[まい]日[にち]", Some(5)),
+            ("日記", "日[にっ]記[き]", None),
+            ("日光", "日[にっ]光[こう]", Some(40)),
+            ("学生", "学[がく]生[せい]", Some(3)),
+            ("先生", "先[せん]生[せい]", Some(8)),
+            ("生活", "生[せい]活[かつ]", None),
+            ("人生", "人[じん]生[せい]", Some(20)),
+            ("本", "本[ほん]", Some(1)),
+        ]
+        .into_iter()
+        .map(|(word, furigana, frequency)| (word.to_string(), furigana.to_string(), frequency))
+        .collect();
+        let id = import_furigana_list("Parts", &rows).await;
+        let mut reading_ids: Vec<i64> = reading_ids(id)
+            .await
+            .into_values()
+            .map(|(_, reading_id)| reading_id)
+            .collect();
+        reading_ids.sort();
+
+        for examples_count in [1, 2, 5] {
+            let (batched, legacy) =
+                batched_and_legacy_parts(&reading_ids, examples_count, id).await;
+            assert_eq!(batched, legacy, "examples_count {}", examples_count);
+        }
+        let (batched, _) = batched_and_legacy_parts(&reading_ids, 2, id).await;
+        let examples = batched
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|parts| parts.as_array().unwrap())
+            .map(|part| part["examples"].as_array().unwrap().len())
+            .sum::<usize>();
+        assert!(examples > 10);
+    });
+}
+
+// Run with `cargo test --lib batched_parts_benchmark -- --ignored --nocapture`
+#[test]
+#[ignore = "imports a JMdict-sized dictionary"]
+fn batched_parts_benchmark() {
+    const WORDS_COUNT: usize = 200_000;
+    const KANJI_COUNT: usize = 2_000;
+    const SYLLABLES: [&str; 12] = [
+        "か", "き", "く", "こ", "さ", "し", "せ", "た", "ち", "に", "ほ", "よ",
+    ];
+
+    // Every kanji has two readings, so each part reading is shared by about 50 words
+    let kanji = |index: usize| char::from_u32(0x4E00 + index as u32).unwrap();
+    let part_reading = |index: usize, variant: usize| {
+        format!(
+            "{}{}",
+            SYLLABLES[index % SYLLABLES.len()],
+            SYLLABLES[(index / SYLLABLES.len() + variant) % SYLLABLES.len()]
+        )
+    };
+    let rows: Vec<(String, String, Option<i64>)> = (0..WORDS_COUNT)
+        .map(|index| {
+            let round = index / KANJI_COUNT;
+            let first = index % KANJI_COUNT;
+            let second = (first + round + 1) % KANJI_COUNT;
+            let furigana = format!(
+                "{}[{}]{}[{}]",
+                kanji(first),
+                part_reading(first, round % 2),
+                kanji(second),
+                part_reading(second, (round + 1) % 2)
+            );
+            let word = format!("{}{}", kanji(first), kanji(second));
+            let frequency = (index % 3 != 0).then_some(index as i64);
+            (word, furigana, frequency)
+        })
+        .collect();
+
+    run(async {
+        let started = Instant::now();
+        let id = import_furigana_list("Benchmark", &rows).await;
+        println!("import: {:?}", started.elapsed());
+
+        // Readings of a 50-round game, spread over the dictionary
+        let mut reading_ids: Vec<i64> = reading_ids(id)
+            .await
+            .into_values()
+            .map(|(_, reading_id)| reading_id)
+            .collect();
+        reading_ids.sort();
+        let step = reading_ids.len() / 50;
+        let game: Vec<i64> = reading_ids.iter().step_by(step).take(50).copied().collect();
+
+        let started = Instant::now();
+        for reading_id in &game {
+            legacy_reading_parts(*reading_id, 5, id).await;
+        }
+        let legacy = started.elapsed();
+
+        let started = Instant::now();
+        get_readings_parts(&game, 5, id).await.unwrap();
+        let batched = started.elapsed();
+        println!("per reading: {:?}, batched: {:?}", legacy, batched);
+
+        let (batched, legacy) = batched_and_legacy_parts(&game, 5, id).await;
+        assert_eq!(batched, legacy);
+
+        // Batches of ten as the game fetches them, the first one orders every candidate
+        let sampling = WordSampling {
+            rarity_bias: 2.0,
+            missed_bias: 1.0,
+            readings_bias: 1.0,
+        };
+        for seed in [Some(7), None] {
+            clear_word_order();
+            let mut batches = Vec::new();
+            for page in 0..5 {
+                let started = Instant::now();
+                let words = get_words(
+                    10,
+                    WordFilter::default(),
+                    sampling.clone(),
+                    seed,
+                    seed.map(|_| page * 10),
+                    None,
+                    5,
+                    id,
+                )
+                .await
+                .unwrap();
+                assert_eq!(words.len(), 10);
+                batches.push(started.elapsed());
+            }
+            println!("biased batches with seed {:?}: {:?}", seed, batches);
+        }
+    });
+}
+
+fn candidate(id: i64, word: &str) -> WordCandidate {
+    WordCandidate {
+        id,
+        word: word.to_string(),
+        frequency: None,
+        readings_count: 1,
+        answers_count: 0,
+        wrong_count: 0,
+    }
+}
+
+fn numbered_candidates(count: i64) -> Vec<WordCandidate> {
+    (1..=count)
+        .map(|id| candidate(id, &format!("単語{}", id)))
+        .collect()
+}
+
+#[test]
+fn seeded_order_depends_on_seed_and_every_character() {
+    let uniform = WordSampling::default();
+    let pick = |seed| sample_words(&numbered_candidates(50), &uniform, Some(seed), 0, 10);
+    assert_eq!(pick(7), pick(7));
+    assert_ne!(pick(7), pick(8));
+
+    // Same length, first three and last characters, only the middle differs
+    let words = ["あいうかえ", "あいうきえ", "あいうくえ"];
+    let values: Vec<f64> = words.iter().map(|word| seeded_uniform(word, 7)).collect();
+    assert!(values[0] != values[1] && values[1] != values[2] && values[0] != values[2]);
+    assert!(values.iter().all(|value| *value > 0.0 && *value <= 1.0));
+}
+
+// Odd ids have no frequency and count as rare, the first ten were always answered wrong
+fn weighted_candidates() -> Vec<WordCandidate> {
+    numbered_candidates(100)
+        .into_iter()
+        .map(|candidate| WordCandidate {
+            frequency: (candidate.id % 2 == 0).then_some(1),
+            answers_count: 2,
+            wrong_count: if candidate.id <= 10 { 2 } else { 0 },
+            ..candidate
+        })
+        .collect()
+}
+
+#[test]
+fn sampling_biases_shift_the_picks() {
+    // Share of the picks over many seeds that satisfy the predicate
+    let share = |sampling: &WordSampling, picked: fn(i64) -> bool| {
+        let ids: Vec<i64> = (1..=20)
+            .flat_map(|seed| sample_words(&weighted_candidates(), sampling, Some(seed), 0, 10))
+            .collect();
+        ids.iter().filter(|id| picked(**id)).count() as f64 / ids.len() as f64
+    };
+    let rare = |id| id % 2 == 1;
+    let missed = |id| id <= 10;
+
+    let uniform = WordSampling::default();
+    let rarity = WordSampling {
+        rarity_bias: 9.0,
+        ..Default::default()
+    };
+    let missed_words = WordSampling {
+        missed_bias: 9.0,
+        ..Default::default()
+    };
+    assert!(share(&uniform, rare) < 0.7);
+    assert!(share(&rarity, rare) > 0.8);
+    assert!(share(&uniform, missed) < 0.25);
+    assert!(share(&missed_words, missed) > 0.4);
+}
+
+#[test]
+fn seeded_pages_are_stable_and_never_overlap() {
+    let sampling = WordSampling {
+        rarity_bias: 2.0,
+        missed_bias: 5.0,
+        readings_bias: 1.0,
+    }
+    .for_seed(Some(7));
+    // Answers given between pages change the wrong counts
+    let page = |index: i64, wrong_count: i64| {
+        let candidates: Vec<WordCandidate> = numbered_candidates(50)
+            .into_iter()
+            .map(|candidate| WordCandidate {
+                frequency: Some(candidate.id * 10),
+                readings_count: candidate.id % 3 + 1,
+                answers_count: 4,
+                wrong_count: if candidate.id % 5 == 0 {
+                    wrong_count
+                } else {
+                    0
+                },
+                ..candidate
+            })
+            .collect();
+        sample_words(&candidates, &sampling, Some(7), index * 10, 10)
+    };
+
+    let pages: Vec<Vec<i64>> = (0..5).map(|index| page(index, index % 5)).collect();
+    for (index, ids) in pages.iter().enumerate() {
+        assert_eq!(*ids, page(index as i64, 0));
+    }
+    let mut ids = pages.concat();
+    ids.sort();
+    assert_eq!(ids, (1..=50).collect::<Vec<i64>>());
+}
+
+#[test]
+fn seeded_games_page_through_one_order() {
+    run(async {
+        let id = import_word_list("Seeded pages", &WORDS).await;
+        let sampling = WordSampling {
+            rarity_bias: 1.0,
+            readings_bias: 2.0,
+            ..Default::default()
+        };
+        let page = |offset| {
+            get_words(
+                2,
+                WordFilter::default(),
+                sampling.clone(),
+                Some(11),
+                Some(offset),
+                None,
+                0,
+                id,
+            )
+        };
+        let words = |words: Vec<WordWithReadings>| -> Vec<String> {
+            words.into_iter().map(|word| word.word).collect()
+        };
+
+        let first = words(page(0).await.unwrap());
+        let second = words(page(2).await.unwrap());
+        assert_eq!(second.len(), 2);
+        assert!(first.iter().all(|word| !second.contains(word)));
+        assert!(words(page(4).await.unwrap()).is_empty());
+
+        // A new game orders the candidates again and gets the same pages
+        add_game_stats(
+            2,
+            10000,
+            WordFilter::default(),
+            sampling.clone(),
+            Some(11),
+            false,
+            None,
+            id,
+        )
+        .await
+        .unwrap();
+        assert_eq!(words(page(0).await.unwrap()), first);
+        assert_eq!(words(page(2).await.unwrap()), second);
+    });
+}
+
+#[test]
+fn uniform_words_are_picked_in_sql() {
+    run(async {
+        let id = import_word_list("Uniform", &WORDS).await;
+        let words = |count| {
+            get_words(
+                count,
+                WordFilter::default(),
+                WordSampling::default(),
+                None,
+                None,
+                None,
+                0,
+                id,
+            )
+        };
+
+        let picked: HashSet<String> = words(2)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|word| word.word)
+            .collect();
+        assert_eq!(picked.len(), 2);
+        assert!(
+            picked
+                .iter()
+                .all(|word| WORDS.iter().any(|(known, _, _)| known == word))
+        );
+        assert_eq!(words(10).await.unwrap().len(), WORDS.len());
+    });
+}
@@ -0,0 +1,1764 @@
+use indexmap::{IndexMap, IndexSet};
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::query_builder::Separated;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Runtime};
+use uuid::Uuid;
+use wana_kana::ConvertJapanese;
+
+use crate::db::{
+    DICTIONARY_SCHEMA_VERSION, DictionaryImportSummary, create_dictionary_database,
+    get_dictionary_guid, import_dictionary,
+};
+
+const GLOSS_SEPARATOR: &str = "␞";
+const SENSE_SEPARATOR: &str = "␝";
+const KEB_SEPARATOR: &str = "␟";
+const TAG_SEPARATOR: &str = ",";
+
+const JMDICT_AUTHOR: &str = "Electronic Dictionary Research and Development Group";
+const JMDICT_LICENSE: &str = "CC BY-SA 4.0";
+const JMDICT_SOURCE_URL: &str = "https://www.edrdg.org/jmdict/j_jmdict.html";
+
+const PROGRESS_INTERVAL: u64 = 1000;
+const INSERT_BATCH_SIZE: usize = 1000;
+
+// Tag elements keep the JMdict entity name (`uk`, `n`, `arch`) instead of its description
+const TAG_ELEMENTS: [&[u8]; 5] = [b"ke_inf", b"re_inf", b"pos", b"misc", b"field"];
+
+// JMdict counts an entry as common if it has one of these priority codes
+const COMMON_PRIORITIES: [&str; 5] = ["news1", "ichi1", "spec1", "spec2", "gai1"];
+const RARE_READING_TAGS: [&str; 3] = ["rk", "ok", "sk"];
+const IRREGULAR_READING_TAG: &str = "ik";
+
+static KANJI_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\p{Han}\x{32FF}\x{337B}-\x{337F}\x{33E0}-\x{33FE}]").unwrap());
+
+static BUILD_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuildStage {
+    Jmdict,
+    WordList,
+    Furigana,
+    Frequency,
+    Words,
+    Readings,
+    PartReadings,
+    Links,
+    Senses,
+    Kanjidic,
+    Kanji,
+    ReadingTags,
+    PitchAccent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildProgress {
+    stage: BuildStage,
+    processed: u64,
+    total: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictionaryBuildReport {
+    pub(crate) guid: String,
+    entries_count: u64,
+    entries_skipped: u64,
+    words_count: u64,
+    readings_count: u64,
+    part_readings_count: u64,
+    links_count: u64,
+    frequencies_count: u64,
+    pitch_accents_count: u64,
+    kanji_count: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordListImportReport {
+    dictionary: DictionaryBuildReport,
+    pub(crate) summary: DictionaryImportSummary,
+}
+
+#[derive(Debug, Default)]
+struct KanjiElement {
+    keb: String,
+    info: Vec<String>,
+    priority: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct ReadingElement {
+    reb: String,
+    restrictions: Vec<String>,
+    info: Vec<String>,
+    priority: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Sense {
+    applies_to: Vec<String>,
+    pos: Vec<String>,
+    misc: Vec<String>,
+    field: Vec<String>,
+    glosses: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct JmdictEntry {
+    kanji: Vec<KanjiElement>,
+    readings: Vec<ReadingElement>,
+    senses: Vec<Sense>,
+}
+
+#[derive(Debug, Default)]
+struct SenseTags {
+    pos: Vec<String>,
+    misc: Vec<String>,
+    field: Vec<String>,
+}
+
+// One per meaning block, ke_pri of the entry applies to all of its senses
+#[derive(Debug, Default)]
+struct EntryTags {
+    priority: Vec<String>,
+    senses: Vec<SenseTags>,
+}
+
+#[derive(Debug)]
+pub(crate) struct BuiltWord {
+    frequency: Option<i64>,
+    meanings: String,
+    entries: Vec<EntryTags>,
+}
+
+#[derive(Debug, Default)]
+struct ReadingInfo {
+    is_common: bool,
+    is_rare: bool,
+    is_irregular: bool,
+    pitch_accents: Vec<i64>,
+}
+
+#[derive(Debug, Default)]
+struct BuiltKanji {
+    jlpt: Option<i64>,
+    grade: Option<i64>,
+    stroke_count: Option<i64>,
+    on_readings: Vec<String>,
+    kun_readings: Vec<String>,
+    meanings: Vec<String>,
+}
+
+// Row ids are the insertion index + 1
+#[derive(Debug, Default)]
+pub(crate) struct DictionaryData {
+    pub(crate) words: IndexMap<String, BuiltWord>,
+    readings: IndexSet<(usize, String)>,
+    part_readings: IndexSet<(String, String)>,
+    links: IndexSet<(usize, usize)>,
+    reading_tags: IndexSet<(usize, String)>,
+    reading_info: HashMap<usize, ReadingInfo>,
+    kanji: IndexMap<String, BuiltKanji>,
+    entries_count: u64,
+    entries_skipped: u64,
+    frequencies_count: u64,
+    pitch_accents_count: u64,
+    version: Option<String>,
+    author: Option<String>,
+    license: Option<String>,
+    source_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct WordListRecord {
+    pub(crate) word: String,
+    #[serde(alias = "readings")]
+    pub(crate) reading: String,
+    #[serde(default)]
+    pub(crate) frequency: Option<i64>,
+    #[serde(default, alias = "meaning")]
+    pub(crate) meanings: Option<String>,
+    #[serde(default)]
+    pub(crate) furigana: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FuriganaItem {
+    text: String,
+    reading: String,
+    furigana: Vec<FuriganaSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FuriganaSegment {
+    ruby: Option<String>,
+    rt: Option<String>,
+}
+
+fn emit_build_progress<R: Runtime>(
+    app: &AppHandle<R>,
+    stage: BuildStage,
+    processed: u64,
+    total: u64,
+) {
+    let progress = BuildProgress {
+        stage,
+        processed,
+        total,
+    };
+
+    if let Err(e) = app.emit("dictionary_build_progress", progress) {
+        eprintln!("Failed to emit build progress: {}", e);
+    }
+}
+
+fn check_build_cancelled() -> Result<(), String> {
+    if BUILD_CANCELLED.load(Ordering::SeqCst) {
+        Err("Build cancelled".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn parse_entities(doctype: &str) -> HashMap<String, String> {
+    doctype
+        .split("<!ENTITY")
+        .skip(1)
+        .filter_map(|declaration| {
+            let (name, rest) = declaration.trim_start().split_once(char::is_whitespace)?;
+            let (value, _) = rest.trim_start().strip_prefix('"')?.split_once('"')?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn add_entry_text(entry: &mut JmdictEntry, element: &[u8], text: String) {
+    match element {
+        b"keb" | b"ke_inf" | b"ke_pri" => {
+            if let Some(kanji) = entry.kanji.last_mut() {
+                match element {
+                    b"keb" => kanji.keb = text,
+                    b"ke_inf" => kanji.info.push(text),
+                    _ => kanji.priority.push(text),
+                }
+            }
+        }
+        b"reb" | b"re_restr" | b"re_inf" | b"re_pri" => {
+            if let Some(reading) = entry.readings.last_mut() {
+                match element {
+                    b"reb" => reading.reb = text,
+                    b"re_restr" => reading.restrictions.push(text),
+                    b"re_inf" => reading.info.push(text),
+                    _ => reading.priority.push(text),
+                }
+            }
+        }
+        b"stagk" | b"pos" | b"misc" | b"field" | b"gloss" => {
+            if let Some(sense) = entry.senses.last_mut() {
+                match element {
+                    b"stagk" => sense.applies_to.push(text),
+                    b"pos" => sense.pos.push(text),
+                    b"misc" => sense.misc.push(text),
+                    b"field" => sense.field.push(text),
+                    _ => sense.glosses.push(text),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn should_process_entry(
+    entry: &JmdictEntry,
+    filter_tags: &HashSet<String>,
+    exclude_tags: &HashSet<String>,
+) -> bool {
+    if filter_tags.is_empty() && exclude_tags.is_empty() {
+        return true;
+    }
+
+    let entry_tags: HashSet<&String> = entry
+        .senses
+        .iter()
+        .flat_map(|sense| sense.misc.iter().chain(&sense.pos))
+        .chain(entry.kanji.iter().flat_map(|kanji| &kanji.info))
+        .chain(entry.readings.iter().flat_map(|reading| &reading.info))
+        .collect();
+
+    if entry_tags.iter().any(|tag| exclude_tags.contains(*tag)) {
+        return false;
+    }
+
+    filter_tags.is_empty() || entry_tags.iter().any(|tag| filter_tags.contains(*tag))
+}
+
+// The same word can appear in several entries, each adds its own meaning block
+fn add_word(data: &mut DictionaryData, word: &str, meanings: String, tags: EntryTags) -> usize {
+    match data.words.get_full_mut(word) {
+        Some((index, _, built)) => {
+            if !built
+                .meanings
+                .split(KEB_SEPARATOR)
+                .any(|block| block == meanings)
+            {
+                if built.meanings.is_empty() {
+                    built.entries.clear();
+                } else {
+                    built.meanings.push_str(KEB_SEPARATOR);
+                }
+                built.meanings.push_str(&meanings);
+                built.entries.push(tags);
+            }
+            index
+        }
+        None => {
+            let built = BuiltWord {
+                frequency: None,
+                meanings,
+                entries: vec![tags],
+            };
+            data.words.insert_full(word.to_string(), built).0
+        }
+    }
+}
+
+fn add_part_reading(data: &mut DictionaryData, reading_index: usize, part: &str, reading: &str) {
+    let (part_index, _) = data
+        .part_readings
+        .insert_full((part.to_string(), reading.to_hiragana()));
+    data.links.insert((reading_index, part_index));
+}
+
+fn process_entry(data: &mut DictionaryData, entry: &JmdictEntry) {
+    let mut keb_meanings: IndexMap<&str, (Vec<String>, Vec<SenseTags>)> = IndexMap::new();
+    // A sense without its own pos keeps the pos of the sense before it
+    let mut pos: &[String] = &[];
+    for sense in &entry.senses {
+        if !sense.pos.is_empty() {
+            pos = &sense.pos;
+        }
+        if sense.glosses.is_empty() {
+            continue;
+        }
+        let sense_meaning = sense.glosses.join(GLOSS_SEPARATOR);
+
+        for kanji in &entry.kanji {
+            let applies = sense.applies_to.is_empty() || sense.applies_to.contains(&kanji.keb);
+            if applies && KANJI_PATTERN.is_match(&kanji.keb) {
+                let (meanings, senses) = keb_meanings.entry(kanji.keb.as_str()).or_default();
+                meanings.push(sense_meaning.clone());
+                senses.push(SenseTags {
+                    pos: pos.to_vec(),
+                    misc: sense.misc.clone(),
+                    field: sense.field.clone(),
+                });
+            }
+        }
+    }
+
+    let mut entry_words: IndexMap<&str, usize> = IndexMap::new();
+    for (keb, (meanings, senses)) in keb_meanings {
+        let priority = entry
+            .kanji
+            .iter()
+            .find(|kanji| kanji.keb == keb)
+            .map(|kanji| kanji.priority.clone())
+            .unwrap_or_default();
+        let tags = EntryTags { priority, senses };
+        let index = add_word(data, keb, meanings.join(SENSE_SEPARATOR), tags);
+        entry_words.insert(keb, index);
+    }
+
+    for reading in &entry.readings {
+        if reading.reb.is_empty() {
+            continue;
+        }
+        let reading_hiragana = reading.reb.to_hiragana();
+
+        for (keb, index) in &entry_words {
+            if reading.restrictions.is_empty()
+                || reading
+                    .restrictions
+                    .iter()
+                    .any(|restriction| restriction == keb)
+            {
+                let reading_index = data
+                    .readings
+                    .insert_full((*index, reading_hiragana.clone()))
+                    .0;
+
+                let kanji_info = entry
+                    .kanji
+                    .iter()
+                    .filter(|kanji| kanji.keb == *keb)
+                    .flat_map(|kanji| &kanji.info);
+                for tag in kanji_info.chain(&reading.info) {
+                    data.reading_tags.insert((reading_index, tag.clone()));
+                }
+
+                // A reading shared by several entries is flagged by any of them
+                let info = data.reading_info.entry(reading_index).or_default();
+                info.is_common |= reading
+                    .priority
+                    .iter()
+                    .any(|priority| COMMON_PRIORITIES.contains(&priority.as_str()));
+                info.is_rare |= reading
+                    .info
+                    .iter()
+                    .any(|tag| RARE_READING_TAGS.contains(&tag.as_str()));
+                info.is_irregular |= reading.info.iter().any(|tag| tag == IRREGULAR_READING_TAG);
+            }
+        }
+    }
+}
+
+fn load_jmdict<R: Runtime>(
+    app: &AppHandle<R>,
+    data: &mut DictionaryData,
+    path: &str,
+    filter_tags: &HashSet<String>,
+    exclude_tags: &HashSet<String>,
+) -> Result<(), String> {
+    let file_size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read JMdict file: {}", e))?
+        .len();
+    let mut reader =
+        Reader::from_file(path).map_err(|e| format!("Failed to open JMdict file: {}", e))?;
+    reader.config_mut().trim_text(true);
+
+    data.author = Some(JMDICT_AUTHOR.to_string());
+    data.license = Some(JMDICT_LICENSE.to_string());
+    data.source_url = Some(JMDICT_SOURCE_URL.to_string());
+
+    let mut entities = HashMap::new();
+    let mut entry = JmdictEntry::default();
+    let mut element = Vec::new();
+    let mut buf = Vec::new();
+    emit_build_progress(app, BuildStage::Jmdict, 0, file_size);
+
+    loop {
+        let event = reader.read_event_into(&mut buf).map_err(|e| {
+            format!(
+                "Failed to parse JMdict at byte {}: {}",
+                reader.buffer_position(),
+                e
+            )
+        })?;
+
+        match event {
+            Event::DocType(doctype) => {
+                entities = parse_entities(&String::from_utf8_lossy(&doctype));
+            }
+            Event::Start(start) => {
+                match start.name().as_ref() {
+                    b"entry" => entry = JmdictEntry::default(),
+                    b"k_ele" => entry.kanji.push(KanjiElement::default()),
+                    b"r_ele" => entry.readings.push(ReadingElement::default()),
+                    b"sense" => entry.senses.push(Sense::default()),
+                    _ => {}
+                }
+                element = start.name().as_ref().to_vec();
+            }
+            Event::Text(text) => {
+                let raw = String::from_utf8_lossy(&text).into_owned();
+                let entity = raw.strip_prefix('&').and_then(|raw| raw.strip_suffix(';'));
+                let value = match entity {
+                    Some(name) if TAG_ELEMENTS.contains(&element.as_slice()) => name.to_string(),
+                    _ => text
+                        .unescape_with(|name| {
+                            entities
+                                .get(name)
+                                .map(String::as_str)
+                                .or_else(|| resolve_predefined_entity(name))
+                        })
+                        .map_err(|e| format!("Failed to parse JMdict text '{}': {}", raw, e))?
+                        .into_owned(),
+                };
+                add_entry_text(&mut entry, &element, value);
+            }
+            Event::End(end) => {
+                if end.name().as_ref() == b"entry" {
+                    if should_process_entry(&entry, filter_tags, exclude_tags) {
+                        process_entry(data, &entry);
+                        data.entries_count += 1;
+                    } else {
+                        data.entries_skipped += 1;
+                    }
+
+                    if (data.entries_count + data.entries_skipped).is_multiple_of(PROGRESS_INTERVAL)
+                    {
+                        check_build_cancelled()?;
+                        let position = reader.buffer_position() as u64;
+                        emit_build_progress(app, BuildStage::Jmdict, position, file_size);
+                    }
+                }
+                element.clear();
+            }
+            // Releases carry their date as <!-- JMdict created: 2024-05-01 -->
+            Event::Comment(comment) => {
+                let comment = String::from_utf8_lossy(&comment);
+                if let Some(created) = comment.trim().strip_prefix("JMdict created:") {
+                    data.version = Some(created.trim().to_string());
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    emit_build_progress(app, BuildStage::Jmdict, file_size, file_size);
+    Ok(())
+}
+
+fn load_furigana<R: Runtime>(
+    app: &AppHandle<R>,
+    data: &mut DictionaryData,
+    path: &str,
+) -> Result<(), String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read furigana file: {}", e))?;
+    let items: Vec<FuriganaItem> = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("Failed to parse furigana file: {}", e))?;
+
+    let total = items.len() as u64;
+    let mut processed_readings = HashSet::new();
+
+    for (processed, item) in items.iter().enumerate() {
+        if (processed as u64).is_multiple_of(PROGRESS_INTERVAL) {
+            check_build_cancelled()?;
+            emit_build_progress(app, BuildStage::Furigana, processed as u64, total);
+        }
+
+        let Some(word_index) = data.words.get_index_of(&item.text) else {
+            continue;
+        };
+        let Some(reading_index) = data
+            .readings
+            .get_index_of(&(word_index, item.reading.to_hiragana()))
+        else {
+            continue;
+        };
+        // Only the first furigana split of a reading is kept
+        if processed_readings.contains(&reading_index) {
+            continue;
+        }
+
+        let mut added = false;
+        for segment in &item.furigana {
+            let (Some(ruby), Some(rt)) = (&segment.ruby, &segment.rt) else {
+                continue;
+            };
+            if ruby.is_empty() || rt.is_empty() {
+                continue;
+            }
+
+            add_part_reading(data, reading_index, ruby, rt);
+            added = true;
+        }
+
+        if added {
+            processed_readings.insert(reading_index);
+        }
+    }
+
+    emit_build_progress(app, BuildStage::Furigana, total, total);
+    Ok(())
+}
+
+// Yomitan frequency entries look like [word, "freq", data], where data is
+// either {"value": ...} or {"reading": ..., "frequency": {"value": ...}}
+fn frequency_value(data: &Value) -> Option<i64> {
+    // Kana-only frequencies are marked with ㋕ and do not apply to the kanji form
+    if data.to_string().contains('㋕') {
+        return None;
+    }
+
+    let value = data.get("value").or_else(|| {
+        data.get("frequency")
+            .and_then(|frequency| frequency.get("value"))
+    })?;
+
+    value
+        .as_i64()
+        .or_else(|| value.as_f64().map(|value| value as i64))
+        .or_else(|| value.as_str().and_then(|value| value.trim().parse().ok()))
+}
+
+fn load_frequency<R: Runtime>(
+    app: &AppHandle<R>,
+    data: &mut DictionaryData,
+    path: &str,
+) -> Result<(), String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read frequency file: {}", e))?;
+    let items: Vec<Value> = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("Failed to parse frequency file: {}", e))?;
+
+    let total = items.len() as u64;
+
+    for (processed, item) in items.iter().enumerate() {
+        if (processed as u64).is_multiple_of(PROGRESS_INTERVAL) {
+            check_build_cancelled()?;
+            emit_build_progress(app, BuildStage::Frequency, processed as u64, total);
+        }
+
+        let Some(word) = item.get(0).and_then(Value::as_str) else {
+            continue;
+        };
+        let Some(new_frequency) = item.get(2).and_then(frequency_value) else {
+            continue;
+        };
+
+        let Some(built) = data.words.get_mut(word) else {
+            continue;
+        };
+        // A word listed several times keeps its best rank
+        if built
+            .frequency
+            .is_none_or(|frequency| new_frequency < frequency)
+        {
+            built.frequency = Some(new_frequency);
+            data.frequencies_count += 1;
+        }
+    }
+
+    emit_build_progress(app, BuildStage::Frequency, total, total);
+    Ok(())
+}
+
+// Accents are downstep positions in morae, like `0` or `1,2`.
+// Kanjium style part of speech notes such as `(名)0` are dropped
+fn parse_pitch_accents(value: &str) -> Vec<i64> {
+    let mut accents = Vec::new();
+    for accent in value.split([',', '，', '、']) {
+        let digits: String = accent.chars().filter(char::is_ascii_digit).collect();
+        if let Ok(accent) = digits.parse()
+            && !accents.contains(&accent)
+        {
+            accents.push(accent);
+        }
+    }
+    accents
+}
+
+// Lines are word, reading and accents separated by tabs, as in Kanjium accents.txt.
+// Kana words can leave the reading empty
+fn load_pitch_accent<R: Runtime>(
+    app: &AppHandle<R>,
+    data: &mut DictionaryData,
+    path: &str,
+) -> Result<(), String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read pitch accent file: {}", e))?;
+    let lines: Vec<&str> = content.trim_start_matches('\u{feff}').lines().collect();
+
+    let total = lines.len() as u64;
+
+    for (processed, line) in lines.iter().enumerate() {
+        if (processed as u64).is_multiple_of(PROGRESS_INTERVAL) {
+            check_build_cancelled()?;
+            emit_build_progress(app, BuildStage::PitchAccent, processed as u64, total);
+        }
+
+        let mut fields = line.split('\t').map(str::trim);
+        let (Some(word), Some(reading), Some(accents)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let reading = if reading.is_empty() { word } else { reading };
+
+        let Some(word_index) = data.words.get_index_of(word) else {
+            continue;
+        };
+        let Some(reading_index) = data
+            .readings
+            .get_index_of(&(word_index, reading.to_hiragana()))
+        else {
+            continue;
+        };
+
+        let accents = parse_pitch_accents(accents);
+        if !accents.is_empty() {
+            data.reading_info
+                .entry(reading_index)
+                .or_default()
+                .pitch_accents = accents;
+            data.pitch_accents_count += 1;
+        }
+    }
+
+    emit_build_progress(app, BuildStage::PitchAccent, total, total);
+    Ok(())
+}
+
+fn attribute_value(start: &BytesStart, name: &str) -> Result<Option<String>, String> {
+    start
+        .try_get_attribute(name)
+        .map_err(|e| format!("Failed to parse KANJIDIC2 attribute: {}", e))?
+        .map(|attribute| {
+            attribute
+                .unescape_value()
+                .map(|value| value.into_owned())
+                .map_err(|e| format!("Failed to parse KANJIDIC2 attribute: {}", e))
+        })
+        .transpose()
+}
+
+// Only the Japanese readings and the English meanings (no m_lang) are kept.
+// A kanji can list several stroke counts, the first one is the accepted count
+fn load_kanjidic<R: Runtime>(
+    app: &AppHandle<R>,
+    data: &mut DictionaryData,
+    path: &str,
+) -> Result<(), String> {
+    let file_size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read KANJIDIC2 file: {}", e))?
+        .len();
+    let mut reader =
+        Reader::from_file(path).map_err(|e| format!("Failed to open KANJIDIC2 file: {}", e))?;
+    reader.config_mut().trim_text(true);
+
+    let mut literal = String::new();
+    let mut kanji = BuiltKanji::default();
+    let mut element = Vec::new();
+    let mut reading_type = None;
+    let mut is_english = false;
+    let mut buf = Vec::new();
+    emit_build_progress(app, BuildStage::Kanjidic, 0, file_size);
+
+    loop {
+        let event = reader.read_event_into(&mut buf).map_err(|e| {
+            format!(
+                "Failed to parse KANJIDIC2 at byte {}: {}",
+                reader.buffer_position(),
+                e
+            )
+        })?;
+
+        match event {
+            Event::Start(start) => {
+                match start.name().as_ref() {
+                    b"character" => {
+                        literal.clear();
+                        kanji = BuiltKanji::default();
+                    }
+                    b"reading" => reading_type = attribute_value(&start, "r_type")?,
+                    b"meaning" => is_english = attribute_value(&start, "m_lang")?.is_none(),
+                    _ => {}
+                }
+                element = start.name().as_ref().to_vec();
+            }
+            Event::Text(text) => {
+                let value = text
+                    .unescape()
+                    .map_err(|e| format!("Failed to parse KANJIDIC2 text: {}", e))?
+                    .into_owned();
+                match element.as_slice() {
+                    b"literal" => literal = value,
+                    b"grade" => kanji.grade = value.parse().ok(),
+                    b"stroke_count" if kanji.stroke_count.is_none() => {
+                        kanji.stroke_count = value.parse().ok()
+                    }
+                    b"jlpt" => kanji.jlpt = value.parse().ok(),
+                    b"reading" => match reading_type.as_deref() {
+                        Some("ja_on") => kanji.on_readings.push(value),
+                        Some("ja_kun") => kanji.kun_readings.push(value),
+                        _ => {}
+                    },
+                    b"meaning" if is_english => kanji.meanings.push(value),
+                    _ => {}
+                }
+            }
+            Event::End(end) => {
+                if end.name().as_ref() == b"character" && !literal.is_empty() {
+                    data.kanji
+                        .insert(std::mem::take(&mut literal), std::mem::take(&mut kanji));
+
+                    if (data.kanji.len() as u64).is_multiple_of(PROGRESS_INTERVAL) {
+                        check_build_cancelled()?;
+                        let position = reader.buffer_position() as u64;
+                        emit_build_progress(app, BuildStage::Kanjidic, position, file_size);
+                    }
+                }
+                element.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    emit_build_progress(app, BuildStage::Kanjidic, file_size, file_size);
+    Ok(())
+}
+
+fn is_kanji(c: char) -> bool {
+    KANJI_PATTERN.is_match(c.encode_utf8(&mut [0; 4]))
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split([';', '；', '、'])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+// Parses `日[に]本[ほん]` or `分[わ]かる`, a bracket reads the kanji right before it.
+// Returns the full reading with the split, or None if it does not spell `word`
+fn parse_furigana(word: &str, furigana: &str) -> Option<(String, Vec<(String, String)>)> {
+    let mut surface = String::new();
+    let mut reading = String::new();
+    let mut parts = Vec::new();
+    let mut rest = furigana;
+
+    while let Some(open) = rest.find('[') {
+        let close = open + rest[open..].find(']')?;
+        let text: String = rest[..open]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let base_start = text
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| is_kanji(*c))
+            .last()
+            .map(|(index, _)| index)?;
+        let part_reading = rest[open + 1..close].trim().to_hiragana();
+        if part_reading.is_empty() {
+            return None;
+        }
+
+        surface.push_str(&text);
+        reading.push_str(&text[..base_start].to_hiragana());
+        reading.push_str(&part_reading);
+        parts.push((text[base_start..].to_string(), part_reading));
+        rest = &rest[close + 1..];
+    }
+
+    let text: String = rest.chars().filter(|c| !c.is_whitespace()).collect();
+    surface.push_str(&text);
+    reading.push_str(&text.to_hiragana());
+
+    (surface == word && !parts.is_empty()).then_some((reading, parts))
+}
+
+// Without explicit furigana only words with a single kanji can be split,
+// the kana around it must match the reading
+fn derive_furigana(word: &str, reading: &str) -> Option<(String, String)> {
+    let mut kanji = word.char_indices().filter(|(_, c)| is_kanji(*c));
+    let (start, kanji_char) = kanji.next()?;
+    if kanji.next().is_some() {
+        return None;
+    }
+
+    let end = start + kanji_char.len_utf8();
+    let part_reading = reading
+        .strip_prefix(word[..start].to_hiragana().as_str())?
+        .strip_suffix(word[end..].to_hiragana().as_str())?;
+
+    (!part_reading.is_empty()).then(|| (kanji_char.to_string(), part_reading.to_string()))
+}
+
+// Returns false if the record has no word or reading
+pub(crate) fn add_word_list_record(data: &mut DictionaryData, record: &WordListRecord) -> bool {
+    let furigana: Vec<(String, Vec<(String, String)>)> = record
+        .furigana
+        .as_deref()
+        .map(split_list)
+        .into_iter()
+        .flatten()
+        .filter_map(|furigana| parse_furigana(&record.word, furigana))
+        .collect();
+
+    let mut readings: Vec<String> = split_list(&record.reading)
+        .map(|reading| reading.to_hiragana())
+        .collect();
+    if readings.is_empty() {
+        readings = furigana
+            .iter()
+            .map(|(reading, _)| reading.clone())
+            .collect();
+    }
+    if record.word.is_empty() || readings.is_empty() {
+        data.entries_skipped += 1;
+        return false;
+    }
+    data.entries_count += 1;
+
+    let meanings: Vec<&str> = record
+        .meanings
+        .as_deref()
+        .map(split_list)
+        .into_iter()
+        .flatten()
+        .collect();
+    let word_index = add_word(
+        data,
+        &record.word,
+        meanings.join(SENSE_SEPARATOR),
+        EntryTags::default(),
+    );
+
+    if let Some(new_frequency) = record.frequency {
+        let built = &mut data.words[word_index];
+        if built
+            .frequency
+            .is_none_or(|frequency| new_frequency < frequency)
+        {
+            built.frequency = Some(new_frequency);
+            data.frequencies_count += 1;
+        }
+    }
+
+    let reading_indexes: Vec<usize> = readings
+        .into_iter()
+        .map(|reading| data.readings.insert_full((word_index, reading)).0)
+        .collect();
+
+    if furigana.is_empty() {
+        for reading_index in reading_indexes {
+            let (_, reading) = &data.readings[reading_index];
+            if let Some((part, part_reading)) = derive_furigana(&record.word, reading) {
+                add_part_reading(data, reading_index, &part, &part_reading);
+            }
+        }
+    }
+
+    for (reading, parts) in furigana {
+        let Some(reading_index) = data.readings.get_index_of(&(word_index, reading)) else {
+            continue;
+        };
+        for (part, part_reading) in parts {
+            add_part_reading(data, reading_index, &part, &part_reading);
+        }
+    }
+
+    true
+}
+
+fn load_word_list<R: Runtime>(
+    app: &AppHandle<R>,
+    data: &mut DictionaryData,
+    path: &str,
+) -> Result<(), String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read word list: {}", e))?;
+    let content = content.trim_start_matches('\u{feff}');
+    let header = content.lines().next().unwrap_or_default();
+    let delimiter = if header.contains('\t') { b'\t' } else { b',' };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers: csv::StringRecord = reader
+        .headers()
+        .map_err(|e| format!("Failed to read word list header: {}", e))?
+        .iter()
+        .map(str::to_lowercase)
+        .collect();
+    reader.set_headers(headers);
+
+    let records: Vec<WordListRecord> = reader
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Failed to parse word list: {}", e))?;
+    let total = records.len() as u64;
+
+    for (processed, record) in records.iter().enumerate() {
+        if (processed as u64).is_multiple_of(PROGRESS_INTERVAL) {
+            check_build_cancelled()?;
+            emit_build_progress(app, BuildStage::WordList, processed as u64, total);
+        }
+
+        add_word_list_record(data, record);
+    }
+
+    emit_build_progress(app, BuildStage::WordList, total, total);
+    Ok(())
+}
+
+async fn insert_batches<'a, T, R: Runtime>(
+    app: &AppHandle<R>,
+    conn: &mut SqliteConnection,
+    stage: BuildStage,
+    insert_sql: &str,
+    rows: &'a [T],
+    push_row: impl Fn(&mut Separated<'_, 'a, Sqlite, &'static str>, &'a T),
+) -> Result<(), String> {
+    let total = rows.len() as u64;
+    let mut processed = 0;
+    emit_build_progress(app, stage, processed, total);
+
+    for chunk in rows.chunks(INSERT_BATCH_SIZE) {
+        check_build_cancelled()?;
+
+        let mut query: QueryBuilder<'a, Sqlite> = QueryBuilder::new(insert_sql);
+        query.push_values(chunk, |mut row, item| push_row(&mut row, item));
+        query
+            .build()
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to write dictionary: {}", e))?;
+
+        processed += chunk.len() as u64;
+        emit_build_progress(app, stage, processed, total);
+    }
+
+    Ok(())
+}
+
+async fn write_dictionary<R: Runtime>(
+    app: &AppHandle<R>,
+    conn: &mut SqliteConnection,
+    data: &DictionaryData,
+    guid: &str,
+    name: &str,
+    description: Option<&str>,
+) -> Result<(), String> {
+    let stats_config = serde_json::json!({
+        "frequencyValues": [1000, 2500, 5000, 10000, 20000, 30000, 50000, 100000],
+        "medals": [
+            {"value": 0, "color": "#gray", "points": 0},
+            {"value": 5, "color": "#cd7f32", "points": 1},
+            {"value": 15, "color": "#c0c0c0", "points": 2},
+            {"value": 50, "color": "#ffd700", "points": 3},
+            {"value": 100, "color": "#b9f2ff", "points": 5}
+        ]
+    })
+    .to_string();
+
+    sqlx::query(include_str!("../queries/build_dictionary_info.sql"))
+        .bind(guid)
+        .bind(name)
+        .bind(stats_config)
+        .bind(description)
+        .bind(&data.version)
+        .bind(&data.author)
+        .bind(&data.license)
+        .bind(&data.source_url)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(DICTIONARY_SCHEMA_VERSION)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to write dictionary info: {}", e))?;
+
+    let words: Vec<(i64, &str, Option<i64>, &str)> = data
+        .words
+        .iter()
+        .enumerate()
+        .map(|(index, (word, built))| {
+            (
+                index as i64 + 1,
+                word.as_str(),
+                built.frequency,
+                built.meanings.as_str(),
+            )
+        })
+        .collect();
+    insert_batches(
+        app,
+        conn,
+        BuildStage::Words,
+        "INSERT INTO word (id, word, frequency, meanings) ",
+        &words,
+        |row, (id, word, frequency, meanings)| {
+            row.push_bind(*id)
+                .push_bind(*word)
+                .push_bind(*frequency)
+                .push_bind(*meanings);
+        },
+    )
+    .await?;
+
+    let default_info = ReadingInfo::default();
+    let readings: Vec<(i64, i64, &str, &ReadingInfo)> = data
+        .readings
+        .iter()
+        .enumerate()
+        .map(|(index, (word_index, reading))| {
+            (
+                index as i64 + 1,
+                *word_index as i64 + 1,
+                reading.as_str(),
+                data.reading_info.get(&index).unwrap_or(&default_info),
+            )
+        })
+        .collect();
+    insert_batches(
+        app,
+        conn,
+        BuildStage::Readings,
+        "INSERT INTO word_reading (id, word_id, word_reading, is_common, is_rare, is_irregular, pitch_accent) ",
+        &readings,
+        |row, (id, word_id, reading, info)| {
+            let pitch_accent = (!info.pitch_accents.is_empty()).then(|| {
+                info.pitch_accents
+                    .iter()
+                    .map(i64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(TAG_SEPARATOR)
+            });
+            row.push_bind(*id)
+                .push_bind(*word_id)
+                .push_bind(*reading)
+                .push_bind(info.is_common)
+                .push_bind(info.is_rare)
+                .push_bind(info.is_irregular)
+                .push_bind(pitch_accent);
+        },
+    )
+    .await?;
+
+    let part_readings: Vec<(i64, &str, &str)> = data
+        .part_readings
+        .iter()
+        .enumerate()
+        .map(|(index, (part, reading))| (index as i64 + 1, part.as_str(), reading.as_str()))
+        .collect();
+    insert_batches(
+        app,
+        conn,
+        BuildStage::PartReadings,
+        "INSERT INTO word_part_reading (id, word_part, word_part_reading) ",
+        &part_readings,
+        |row, (id, part, reading)| {
+            row.push_bind(*id).push_bind(*part).push_bind(*reading);
+        },
+    )
+    .await?;
+
+    let links: Vec<(i64, i64)> = data
+        .links
+        .iter()
+        .map(|(reading_index, part_index)| (*reading_index as i64 + 1, *part_index as i64 + 1))
+        .collect();
+    insert_batches(
+        app,
+        conn,
+        BuildStage::Links,
+        "INSERT INTO word_reading_word_part_reading (word_reading_id, word_part_reading_id) ",
+        &links,
+        |row, (reading_id, part_reading_id)| {
+            row.push_bind(*reading_id).push_bind(*part_reading_id);
+        },
+    )
+    .await?;
+
+    // Senses line up with the meaning blocks, untagged ones are left out
+    let senses: Vec<(i64, i64, i64, String, String, String, String)> = data
+        .words
+        .values()
+        .enumerate()
+        .flat_map(|(word_index, built)| {
+            built
+                .entries
+                .iter()
+                .enumerate()
+                .flat_map(move |(entry_index, entry)| {
+                    entry
+                        .senses
+                        .iter()
+                        .enumerate()
+                        .filter(move |(_, sense)| {
+                            !entry.priority.is_empty()
+                                || !sense.pos.is_empty()
+                                || !sense.misc.is_empty()
+                                || !sense.field.is_empty()
+                        })
+                        .map(move |(sense_index, sense)| {
+                            (
+                                word_index as i64 + 1,
+                                entry_index as i64,
+                                sense_index as i64,
+                                sense.pos.join(TAG_SEPARATOR),
+                                sense.misc.join(TAG_SEPARATOR),
+                                sense.field.join(TAG_SEPARATOR),
+                                entry.priority.join(TAG_SEPARATOR),
+                            )
+                        })
+                })
+        })
+        .collect();
+    insert_batches(
+        app,
+        conn,
+        BuildStage::Senses,
+        "INSERT INTO word_sense (word_id, entry_index, sense_index, pos, misc, field, priority) ",
+        &senses,
+        |row, (word_id, entry_index, sense_index, pos, misc, field, priority)| {
+            row.push_bind(*word_id)
+                .push_bind(*entry_index)
+                .push_bind(*sense_index)
+                .push_bind(pos.as_str())
+                .push_bind(misc.as_str())
+                .push_bind(field.as_str())
+                .push_bind(priority.as_str());
+        },
+    )
+    .await?;
+
+    let reading_tags: Vec<&(usize, String)> = data.reading_tags.iter().collect();
+    insert_batches(
+        app,
+        conn,
+        BuildStage::ReadingTags,
+        "INSERT INTO word_reading_tag (word_reading_id, tag) ",
+        &reading_tags,
+        |row, (reading_index, tag)| {
+            row.push_bind(*reading_index as i64 + 1)
+                .push_bind(tag.as_str());
+        },
+    )
+    .await?;
+
+    let kanji: Vec<(&String, &BuiltKanji)> = data.kanji.iter().collect();
+    insert_batches(
+        app,
+        conn,
+        BuildStage::Kanji,
+        "INSERT INTO kanji (kanji, jlpt, grade, stroke_count, on_readings, kun_readings, meanings) ",
+        &kanji,
+        |row, (literal, built)| {
+            row.push_bind(literal.as_str())
+                .push_bind(built.jlpt)
+                .push_bind(built.grade)
+                .push_bind(built.stroke_count)
+                .push_bind(built.on_readings.join(TAG_SEPARATOR))
+                .push_bind(built.kun_readings.join(TAG_SEPARATOR))
+                .push_bind(built.meanings.join(GLOSS_SEPARATOR));
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_dictionary_build() {
+    BUILD_CANCELLED.store(true, Ordering::SeqCst);
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn build_dictionary<R: Runtime>(
+    app: AppHandle<R>,
+    jmdict_path: String,
+    furigana_path: Option<String>,
+    frequency_path: Option<String>,
+    kanjidic_path: Option<String>,
+    pitch_accent_path: Option<String>,
+    output_path: &str,
+    name: &str,
+    description: Option<&str>,
+    filter_tags: Option<Vec<String>>,
+    exclude_tags: Option<Vec<String>>,
+) -> Result<DictionaryBuildReport, String> {
+    BUILD_CANCELLED.store(false, Ordering::SeqCst);
+
+    let load_app = app.clone();
+    let data = tokio::task::spawn_blocking(move || {
+        let filter_tags: HashSet<String> = filter_tags.unwrap_or_default().into_iter().collect();
+        let exclude_tags: HashSet<String> = exclude_tags.unwrap_or_default().into_iter().collect();
+
+        let mut data = DictionaryData::default();
+        load_jmdict(
+            &load_app,
+            &mut data,
+            &jmdict_path,
+            &filter_tags,
+            &exclude_tags,
+        )?;
+        if let Some(path) = furigana_path {
+            load_furigana(&load_app, &mut data, &path)?;
+        }
+        if let Some(path) = frequency_path {
+            load_frequency(&load_app, &mut data, &path)?;
+        }
+        if let Some(path) = kanjidic_path {
+            load_kanjidic(&load_app, &mut data, &path)?;
+        }
+        if let Some(path) = pitch_accent_path {
+            load_pitch_accent(&load_app, &mut data, &path)?;
+        }
+        Ok::<_, String>(data)
+    })
+    .await
+    .map_err(|e| format!("Build failed: {}", e))??;
+
+    let guid = Uuid::new_v4().to_string();
+    save_dictionary(&app, &data, output_path, &guid, name, description).await
+}
+
+async fn save_dictionary<R: Runtime>(
+    app: &AppHandle<R>,
+    data: &DictionaryData,
+    output_path: &str,
+    guid: &str,
+    name: &str,
+    description: Option<&str>,
+) -> Result<DictionaryBuildReport, String> {
+    let dict_pool = create_dictionary_database(output_path).await?;
+
+    let mut tx = dict_pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    let write_result = write_dictionary(app, &mut tx, data, guid, name, description).await;
+
+    let result = match write_result {
+        Ok(_) => tx
+            .commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e)),
+        Err(e) => {
+            let _ = tx.rollback().await;
+            Err(e)
+        }
+    };
+
+    dict_pool.close().await;
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(output_path);
+        return Err(e);
+    }
+
+    Ok(DictionaryBuildReport {
+        guid: guid.to_string(),
+        entries_count: data.entries_count,
+        entries_skipped: data.entries_skipped,
+        words_count: data.words.len() as u64,
+        readings_count: data.readings.len() as u64,
+        part_readings_count: data.part_readings.len() as u64,
+        links_count: data.links.len() as u64,
+        frequencies_count: data.frequencies_count,
+        pitch_accents_count: data.pitch_accents_count,
+        kanji_count: data.kanji.len() as u64,
+    })
+}
+
+// A list imported into an existing dictionary keeps its guid,
+// so the words are merged and their cards and stats are kept
+#[tauri::command]
+pub async fn import_word_list<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    name: &str,
+    description: Option<&str>,
+    dictionary_id: Option<i64>,
+) -> Result<WordListImportReport, String> {
+    BUILD_CANCELLED.store(false, Ordering::SeqCst);
+
+    let guid = match dictionary_id {
+        Some(id) => get_dictionary_guid(id).await?,
+        None => Uuid::new_v4().to_string(),
+    };
+
+    let load_app = app.clone();
+    let data = tokio::task::spawn_blocking(move || {
+        let mut data = DictionaryData::default();
+        load_word_list(&load_app, &mut data, &path)?;
+        Ok::<_, String>(data)
+    })
+    .await
+    .map_err(|e| format!("Import failed: {}", e))??;
+
+    if data.words.is_empty() {
+        return Err("Word list contains no words with readings".to_string());
+    }
+
+    let (dictionary, summary) =
+        import_dictionary_data(app, &data, &guid, name, description).await?;
+
+    Ok(WordListImportReport {
+        dictionary,
+        summary,
+    })
+}
+
+// The data is written to a temporary dictionary file and imported like any other dictionary
+pub(crate) async fn import_dictionary_data<R: Runtime>(
+    app: AppHandle<R>,
+    data: &DictionaryData,
+    guid: &str,
+    name: &str,
+    description: Option<&str>,
+) -> Result<(DictionaryBuildReport, DictionaryImportSummary), String> {
+    let dict_path = std::env::temp_dir()
+        .join(format!("kanjilab_import_{}.db", Uuid::new_v4()))
+        .into_os_string()
+        .into_string()
+        .map_err(|_| "Invalid temporary path".to_string())?;
+
+    let result = match save_dictionary(&app, data, &dict_path, guid, name, description).await {
+        Ok(report) => import_dictionary(app, &dict_path)
+            .await
+            .map(|summary| (report, summary)),
+        Err(e) => Err(e),
+    };
+
+    let _ = std::fs::remove_file(&dict_path);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::test::mock_app;
+
+    const JMDICT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE JMdict [
+<!ENTITY n "noun (common) (futsuumeishi)">
+<!ENTITY arch "archaic">
+<!ENTITY ateji "ateji (phonetic) reading">
+<!ENTITY rk "rarely used kana form">
+<!ENTITY ik "word containing irregular kana usage">
+]>
+<JMdict>
+<!-- JMdict created: 2024-05-01 -->
+<entry>
+<ent_seq>1</ent_seq>
+<k_ele><keb>日本</keb><ke_pri>news1</ke_pri></k_ele>
+<k_ele><keb>日夲</keb><ke_inf>&ateji;</ke_inf></k_ele>
+<r_ele><reb>にほん</reb><re_pri>news1</re_pri></r_ele>
+<r_ele><reb>ニッポン</reb><re_restr>日本</re_restr><re_inf>&rk;</re_inf></r_ele>
+<sense><pos>&n;</pos><gloss>Japan</gloss></sense>
+<sense><stagk>日本</stagk><gloss>Nippon</gloss><gloss>Land of the Rising Sun</gloss></sense>
+</entry>
+<entry>
+<ent_seq>2</ent_seq>
+<k_ele><keb>古語</keb></k_ele>
+<r_ele><reb>こご</reb><re_inf>&ik;</re_inf><re_pri>ichi2</re_pri></r_ele>
+<sense><pos>&n;</pos><misc>&arch;</misc><gloss>archaic word</gloss></sense>
+</entry>
+<entry>
+<ent_seq>3</ent_seq>
+<r_ele><reb>かな</reb></r_ele>
+<sense><gloss>kana only</gloss></sense>
+</entry>
+</JMdict>"#;
+
+    const KANJIDIC: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kanjidic2>
+<character>
+<literal>日</literal>
+<misc><grade>1</grade><stroke_count>4</stroke_count><stroke_count>5</stroke_count><jlpt>4</jlpt></misc>
+<reading_meaning><rmgroup>
+<reading r_type="pinyin">ri4</reading>
+<reading r_type="ja_on">ニチ</reading>
+<reading r_type="ja_kun">ひ</reading>
+<meaning>day</meaning>
+<meaning>sun</meaning>
+<meaning m_lang="fr">jour</meaning>
+</rmgroup></reading_meaning>
+</character>
+</kanjidic2>"#;
+
+    fn temp_file(name: &str, content: &str) -> String {
+        let path = std::env::temp_dir()
+            .join(format!("kanjilab_test_{}_{}", std::process::id(), name))
+            .into_os_string()
+            .into_string()
+            .unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn readings(data: &DictionaryData, word: &str) -> Vec<String> {
+        let word_index = data.words.get_index_of(word).unwrap();
+        data.readings
+            .iter()
+            .filter(|(index, _)| *index == word_index)
+            .map(|(_, reading)| reading.clone())
+            .collect()
+    }
+
+    fn parts(data: &DictionaryData, word: &str, reading: &str) -> Vec<(String, String)> {
+        let word_index = data.words.get_index_of(word).unwrap();
+        let reading_index = data
+            .readings
+            .get_index_of(&(word_index, reading.to_string()))
+            .unwrap();
+        data.links
+            .iter()
+            .filter(|(index, _)| *index == reading_index)
+            .map(|(_, part_index)| data.part_readings[*part_index].clone())
+            .collect()
+    }
+
+    fn load_test_jmdict(exclude_tags: &[&str]) -> DictionaryData {
+        let path = temp_file("jmdict.xml", JMDICT);
+        let exclude_tags = exclude_tags.iter().map(|tag| tag.to_string()).collect();
+        let mut data = DictionaryData::default();
+        load_jmdict(
+            mock_app().handle(),
+            &mut data,
+            &path,
+            &HashSet::new(),
+            &exclude_tags,
+        )
+        .unwrap();
+        data
+    }
+
+    #[test]
+    fn jmdict_entries_become_words() {
+        let data = load_test_jmdict(&[]);
+
+        assert_eq!(data.version.as_deref(), Some("2024-05-01"));
+        assert_eq!((data.entries_count, data.entries_skipped), (3, 0));
+        assert_eq!(
+            data.words.keys().collect::<Vec<_>>(),
+            ["日本", "日夲", "古語"]
+        );
+
+        let nihon = &data.words["日本"];
+        assert_eq!(
+            nihon.meanings,
+            ["Japan", "Nippon␞Land of the Rising Sun"].join(SENSE_SEPARATOR)
+        );
+        assert_eq!(nihon.entries[0].priority, ["news1"]);
+        // The second sense keeps the pos of the first one
+        assert_eq!(nihon.entries[0].senses[1].pos, ["n"]);
+        assert_eq!(data.words["日夲"].meanings, "Japan");
+        assert_eq!(data.words["古語"].entries[0].senses[0].misc, ["arch"]);
+
+        assert_eq!(readings(&data, "日本"), ["にほん", "にっぽん"]);
+        assert_eq!(readings(&data, "日夲"), ["にほん"]);
+        let ateji_reading = data
+            .readings
+            .get_index_of(&(
+                data.words.get_index_of("日夲").unwrap(),
+                "にほん".to_string(),
+            ))
+            .unwrap();
+        assert!(
+            data.reading_tags
+                .contains(&(ateji_reading, "ateji".to_string()))
+        );
+    }
+
+    fn reading_info<'a>(data: &'a DictionaryData, word: &str, reading: &str) -> &'a ReadingInfo {
+        let word_index = data.words.get_index_of(word).unwrap();
+        let reading_index = data
+            .readings
+            .get_index_of(&(word_index, reading.to_string()))
+            .unwrap();
+        &data.reading_info[&reading_index]
+    }
+
+    #[test]
+    fn reading_flags_and_pitch_accents_are_kept() {
+        let mut data = load_test_jmdict(&[]);
+
+        let flags = |info: &ReadingInfo| (info.is_common, info.is_rare, info.is_irregular);
+        assert_eq!(
+            flags(reading_info(&data, "日本", "にほん")),
+            (true, false, false)
+        );
+        assert_eq!(
+            flags(reading_info(&data, "日本", "にっぽん")),
+            (false, true, false)
+        );
+        assert_eq!(
+            flags(reading_info(&data, "日夲", "にほん")),
+            (true, false, false)
+        );
+        // ichi2 is not one of the common priorities
+        assert_eq!(
+            flags(reading_info(&data, "古語", "こご")),
+            (false, false, true)
+        );
+
+        let accents = temp_file(
+            "accents.txt",
+            "日本\tにほん\t2\n日本\tニッポン\t(名)3,(名)3,0\n古語\tこご\t\n未知\tみち\t0\n",
+        );
+        load_pitch_accent(mock_app().handle(), &mut data, &accents).unwrap();
+        assert_eq!(data.pitch_accents_count, 2);
+        assert_eq!(reading_info(&data, "日本", "にほん").pitch_accents, [2]);
+        assert_eq!(
+            reading_info(&data, "日本", "にっぽん").pitch_accents,
+            [3, 0]
+        );
+        assert!(reading_info(&data, "古語", "こご").pitch_accents.is_empty());
+        assert!(
+            reading_info(&data, "日夲", "にほん")
+                .pitch_accents
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn jmdict_exclude_tags_skip_entries() {
+        let data = load_test_jmdict(&["arch"]);
+
+        assert_eq!((data.entries_count, data.entries_skipped), (2, 1));
+        assert!(!data.words.contains_key("古語"));
+    }
+
+    #[test]
+    fn furigana_and_frequency_files_apply_to_words() {
+        let mut data = load_test_jmdict(&[]);
+        let furigana = temp_file(
+            "furigana.json",
+            r#"[
+                {"text": "日本", "reading": "にほん", "furigana": [{"ruby": "日", "rt": "に"}, {"ruby": "本", "rt": "ほん"}]},
+                {"text": "日本", "reading": "にほん", "furigana": [{"ruby": "日本", "rt": "にほん"}]},
+                {"text": "未知", "reading": "みち", "furigana": [{"ruby": "未", "rt": "み"}]}
+            ]"#,
+        );
+        let frequency = temp_file(
+            "frequency.json",
+            r#"[
+                ["日本", "freq", {"value": 100}],
+                ["日本", "freq", {"reading": "にほん", "frequency": {"value": 50}}],
+                ["日本", "freq", {"value": 1, "displayValue": "1㋕"}],
+                ["古語", "freq", {"value": "7"}]
+            ]"#,
+        );
+        let app = mock_app();
+        load_furigana(app.handle(), &mut data, &furigana).unwrap();
+        load_frequency(app.handle(), &mut data, &frequency).unwrap();
+
+        assert_eq!(
+            parts(&data, "日本", "にほん"),
+            [
+                ("日".to_string(), "に".to_string()),
+                ("本".to_string(), "ほん".to_string())
+            ]
+        );
+        assert!(parts(&data, "日本", "にっぽん").is_empty());
+        assert_eq!(data.words["日本"].frequency, Some(50));
+        assert_eq!(data.words["古語"].frequency, Some(7));
+        assert_eq!(data.words["日夲"].frequency, None);
+    }
+
+    #[test]
+    fn kanjidic_keeps_japanese_readings_and_english_meanings() {
+        let path = temp_file("kanjidic.xml", KANJIDIC);
+        let mut data = DictionaryData::default();
+        load_kanjidic(mock_app().handle(), &mut data, &path).unwrap();
+
+        let kanji = &data.kanji["日"];
+        assert_eq!(
+            (kanji.grade, kanji.stroke_count, kanji.jlpt),
+            (Some(1), Some(4), Some(4))
+        );
+        assert_eq!(kanji.on_readings, ["ニチ"]);
+        assert_eq!(kanji.kun_readings, ["ひ"]);
+        assert_eq!(kanji.meanings, ["day", "sun"]);
+    }
+
+    #[test]
+    fn word_lists_are_read_as_csv_or_tsv() {
+        let app = mock_app();
+
+        let csv = temp_file(
+            "list.csv",
+            "\u{feff}Word,Readings,Frequency,Meaning,Furigana\n\
+             日本,にほん;ニッポン,10,Japan;Nippon,\n\
+             分かる,,5,to understand,分[わ]かる\n\
+             ,かな,,,\n",
+        );
+        let mut data = DictionaryData::default();
+        load_word_list(app.handle(), &mut data, &csv).unwrap();
+        assert_eq!((data.entries_count, data.entries_skipped), (2, 1));
+        assert_eq!(readings(&data, "日本"), ["にほん", "にっぽん"]);
+        assert_eq!(data.words["日本"].frequency, Some(10));
+        assert_eq!(
+            data.words["日本"].meanings,
+            ["Japan", "Nippon"].join(SENSE_SEPARATOR)
+        );
+        assert_eq!(readings(&data, "分かる"), ["わかる"]);
+        assert_eq!(
+            parts(&data, "分かる", "わかる"),
+            [("分".to_string(), "わ".to_string())]
+        );
+
+        let tsv = temp_file("list.tsv", "word\treading\n水\tみず\n");
+        let mut data = DictionaryData::default();
+        load_word_list(app.handle(), &mut data, &tsv).unwrap();
+        assert_eq!(
+            parts(&data, "水", "みず"),
+            [("水".to_string(), "みず".to_string())]
+        );
+    }
+
+    #[test]
+    fn furigana_is_parsed_per_kanji() {
+        assert_eq!(
+            parse_furigana("日本", "日[に]本[ほん]"),
+            Some((
+                "にほん".to_string(),
+                vec![
+                    ("日".to_string(), "に".to_string()),
+                    ("本".to_string(), "ほん".to_string())
+                ]
+            ))
+        );
+        assert_eq!(
+            parse_furigana("お茶", "お 茶[ちゃ]"),
+            Some((
+                "おちゃ".to_string(),
+                vec![("茶".to_string(), "ちゃ".to_string())]
+            ))
+        );
+        assert_eq!(parse_furigana("日本", "日[に]"), None);
+        assert_eq!(parse_furigana("日本", "日本"), None);
+        assert_eq!(parse_furigana("日本", "日[]本[ほん]"), None);
+
+        assert_eq!(
+            derive_furigana("分かる", "わかる"),
+            Some(("分".to_string(), "わ".to_string()))
+        );
+        assert_eq!(derive_furigana("日本", "にほん"), None);
+        assert_eq!(derive_furigana("分かる", "わかった"), None);
+    }
+
+    #[test]
+    fn frequency_values_and_entities_are_parsed() {
+        assert_eq!(frequency_value(&serde_json::json!({"value": 10})), Some(10));
+        assert_eq!(
+            frequency_value(&serde_json::json!({"value": 10.7})),
+            Some(10)
+        );
+        assert_eq!(
+            frequency_value(&serde_json::json!({"frequency": {"value": " 12 "}})),
+            Some(12)
+        );
+        assert_eq!(
+            frequency_value(&serde_json::json!({"value": 3, "displayValue": "3㋕"})),
+            None
+        );
+        assert_eq!(frequency_value(&serde_json::json!({"reading": "x"})), None);
+
+        let entities =
+            parse_entities(r#"JMdict [ <!ENTITY n "noun"> <!ENTITY uk "usually kana"> ]"#);
+        assert_eq!(entities["n"], "noun");
+        assert_eq!(entities["uk"], "usually kana");
+        assert_eq!(
+            split_list(" a; b；c、、 ").collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use chrono::{DateTime, Utc};
+use rs_fsrs::{Card, FSRS, Parameters, Rating, State};
+
+const DECAY: f64 = -0.5;
+const FACTOR: f64 = 19.0 / 81.0;
+
+const MIN_PROBABILITY: f64 = 0.0001;
+const MIN_WEIGHT: f64 = 0.001;
+const MAX_WEIGHT: f64 = 100.0;
+const INITIAL_STEP: f64 = 0.2;
+const MIN_STEP: f64 = 0.005;
+const MAX_PASSES: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct Review {
+    pub answer_stats_id: Option<i64>,
+    pub timestamp: DateTime<Utc>,
+    pub is_correct: bool,
+    pub rating: Rating,
+}
+
+pub fn scheduler(weights: Option<&[f64]>) -> FSRS {
+    let mut parameters = Parameters::default();
+    if let Some(weights) = weights.filter(|weights| weights.len() == parameters.w.len()) {
+        parameters.w.copy_from_slice(weights);
+    }
+    FSRS::new(parameters)
+}
+
+pub fn default_weights() -> Vec<f64> {
+    Parameters::default().w.to_vec()
+}
+
+pub fn retrievability(card: &Card, now: DateTime<Utc>) -> f64 {
+    if matches!(card.state, State::New) || card.stability <= 0.0 {
+        return 0.0;
+    }
+
+    let elapsed_days = (now - card.last_review).num_days().max(0) as f64;
+    (1.0 + FACTOR * elapsed_days / card.stability).powf(DECAY)
+}
+
+// Returns the card before and after every review
+pub fn replay(fsrs: &FSRS, reviews: &[Review]) -> Vec<(Card, Card)> {
+    let mut card = Card::new();
+    reviews
+        .iter()
+        .map(|review| {
+            let previous = card.clone();
+            card = fsrs.repeat(previous.clone(), review.timestamp)[&review.rating]
+                .card
+                .clone();
+            (previous, card.clone())
+        })
+        .collect()
+}
+
+// Only reviews made at least a day after the previous one are scored,
+// same-day repeats measure short-term memory
+pub fn log_loss(history: &[Vec<Review>], weights: &[f64]) -> (f64, usize) {
+    let fsrs = scheduler(Some(weights));
+    let mut total = 0.0;
+    let mut count = 0;
+
+    for reviews in history {
+        let mut card = Card::new();
+        for review in reviews {
+            if !matches!(card.state, State::New)
+                && (review.timestamp - card.last_review).num_days() >= 1
+            {
+                let probability = retrievability(&card, review.timestamp)
+                    .clamp(MIN_PROBABILITY, 1.0 - MIN_PROBABILITY);
+                total -= if review.is_correct {
+                    probability.ln()
+                } else {
+                    (1.0 - probability).ln()
+                };
+                count += 1;
+            }
+            card = fsrs.repeat(card, review.timestamp)[&review.rating]
+                .card
+                .clone();
+        }
+    }
+
+    if count == 0 {
+        (f64::NAN, 0)
+    } else {
+        (total / count as f64, count)
+    }
+}
+
+// Coordinate descent with multiplicative steps,
+// the step shrinks whenever a full pass finds nothing better
+pub fn optimize(history: &[Vec<Review>], initial_weights: &[f64]) -> Vec<f64> {
+    let mut weights = initial_weights.to_vec();
+    let (mut best_loss, _) = log_loss(history, &weights);
+    if best_loss.is_nan() {
+        return weights;
+    }
+
+    let mut step = INITIAL_STEP;
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+
+        for i in 0..weights.len() {
+            for factor in [1.0 + step, 1.0 - step] {
+                let mut candidate = weights.clone();
+                candidate[i] = (candidate[i] * factor).clamp(MIN_WEIGHT, MAX_WEIGHT);
+
+                let (loss, _) = log_loss(history, &candidate);
+                if loss < best_loss {
+                    best_loss = loss;
+                    weights = candidate;
+                    improved = true;
+                    break;
+                }
+            }
+        }
+
+        if !improved {
+            step /= 2.0;
+            if step < MIN_STEP {
+                break;
+            }
+        }
+    }
+
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    fn review(day: i64, is_correct: bool) -> Review {
+        Review {
+            answer_stats_id: None,
+            timestamp: start() + Duration::days(day),
+            is_correct,
+            rating: if is_correct {
+                Rating::Good
+            } else {
+                Rating::Again
+            },
+        }
+    }
+
+    fn history() -> Vec<Vec<Review>> {
+        vec![
+            vec![
+                review(0, true),
+                review(3, true),
+                review(10, true),
+                review(30, true),
+            ],
+            vec![
+                review(0, false),
+                review(1, false),
+                review(2, true),
+                review(6, false),
+            ],
+            vec![
+                review(0, true),
+                review(2, false),
+                review(3, true),
+                review(9, true),
+            ],
+            vec![
+                review(0, true),
+                review(5, true),
+                review(20, false),
+                review(21, true),
+            ],
+        ]
+    }
+
+    #[test]
+    fn retrievability_decays_from_review() {
+        let card = Card {
+            stability: 10.0,
+            state: State::Review,
+            last_review: start(),
+            ..Card::new()
+        };
+
+        assert_eq!(retrievability(&card, start()), 1.0);
+        assert!((retrievability(&card, start() + Duration::days(10)) - 0.9).abs() < 1e-9);
+        assert!(
+            retrievability(&card, start() + Duration::days(30))
+                < retrievability(&card, start() + Duration::days(20))
+        );
+        assert_eq!(retrievability(&Card::new(), start()), 0.0);
+    }
+
+    #[test]
+    fn replay_returns_every_step() {
+        let reviews = &history()[1];
+        let steps = replay(&scheduler(None), reviews);
+
+        assert_eq!(steps.len(), reviews.len());
+        assert_eq!(steps[0].0.reps, 0);
+        for ((_, card), (next_previous, _)) in steps.iter().zip(&steps[1..]) {
+            assert_eq!(card.reps, next_previous.reps);
+            assert_eq!(card.last_review, next_previous.last_review);
+        }
+        assert_eq!(steps.last().unwrap().1.reps as usize, reviews.len());
+    }
+
+    #[test]
+    fn log_loss_skips_same_day_reviews() {
+        assert!(log_loss(&[], &default_weights()).0.is_nan());
+
+        let same_day = vec![vec![review(0, true), review(0, false)]];
+        let (loss, count) = log_loss(&same_day, &default_weights());
+        assert!(loss.is_nan());
+        assert_eq!(count, 0);
+
+        let (loss, count) = log_loss(&history(), &default_weights());
+        assert!(loss.is_finite() && loss > 0.0);
+        assert_eq!(count, 12);
+    }
+
+    #[test]
+    fn optimize_never_increases_loss() {
+        let initial = default_weights();
+        assert_eq!(optimize(&[], &initial), initial);
+
+        let weights = optimize(&history(), &initial);
+        assert_eq!(weights.len(), initial.len());
+        assert!(
+            weights
+                .iter()
+                .all(|weight| (MIN_WEIGHT..=MAX_WEIGHT).contains(weight))
+        );
+        assert!(log_loss(&history(), &weights).0 <= log_loss(&history(), &initial).0);
+    }
+}
@@ -1,6 +1,9 @@
+pub mod anki;
 pub mod crypto;
 pub mod db;
+pub mod dict_builder;
 pub mod fonts;
+pub mod fsrs;
 pub mod tools;
 use db::{close_db, init_db};
 use tokio::runtime::Runtime;
@@ -19,7 +22,11 @@ pub fn run() {
             fonts::get_font_info,
             fonts::get_all_fonts_info,
             db::get_words,
+            db::get_due_words,
             db::get_words_count,
+            db::search_words,
+            db::get_word_details,
+            db::get_kanji_info,
             db::get_overall_stats,
             db::add_answer_stats,
             db::add_game_stats,
@@ -35,8 +42,28 @@ pub fn run() {
             db::get_all_users,
             db::get_dictionaries,
             db::delete_dictionary,
+            db::get_deleted_dictionaries,
+            db::purge_dictionary,
             db::import_dictionary,
+            db::cancel_dictionary_import,
+            db::validate_dictionary,
+            db::export_dictionary,
+            dict_builder::build_dictionary,
+            dict_builder::import_word_list,
+            anki::get_anki_note_types,
+            anki::import_anki_deck,
+            dict_builder::cancel_dictionary_build,
             db::update_card_fsrs,
+            db::get_fsrs_settings,
+            db::set_fsrs_settings,
+            db::optimize_fsrs_parameters,
+            db::get_card_history,
+            db::get_retention_forecast,
+            db::rebuild_cards_fsrs,
+            db::set_card_suspended,
+            db::bury_card,
+            db::reset_card,
+            db::set_card_note,
             crypto::sign_message,
             crypto::verify_signature,
             crypto::get_accounts,
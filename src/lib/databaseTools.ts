@@ -1,7 +1,65 @@
 import { getSettings } from "$lib/globalSettings.svelte";
-import type { AnswerStats, AnswerStreaks, DictionaryInfo, DictionaryStatsConfig, GameStats, RawDictionaryInfo, User, WordInfo } from "$lib/types";
+import type { AnkiImportReport, AnkiNoteType, AnswerStats, AnswerStreaks, BuildProgress, CardRebuildReport, DeletedDictionary, DictionaryBuildReport, DictionaryImportSummary, DictionaryInfo, DictionaryStatsConfig, DictionaryValidationReport, FsrsOptimizationResult, FsrsSettings, GameStats, ImportProgress, KanjiInfo, RawDictionaryInfo, RetentionForecast, ReviewLogEntry, User, WordFilter, WordInfo, WordListImportReport, WordSampling, WordSearchPage } from "$lib/types";
 import type { StatsInfo } from "$lib/types";
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+export function getWordFilter(): WordFilter
+{
+	const wordPart = getSettings().wordPart.get();
+	const wordPartReading = getSettings().wordPartReading.get();
+
+	return {
+		minFrequency: getSettings().minFrequency.get(),
+		maxFrequency: getSettings().usingMaxFrequency.get() ? getSettings().maxFrequency.get() : null,
+		wordParts: wordPart ? [{ wordPart, wordPartReading: wordPartReading || null }] : [],
+		minKanjiJlptN: getSettings().minKanjiJlptN.get(),
+		maxKanjiGrade: getSettings().maxKanjiGrade.get(),
+	};
+}
+
+export function getWordSampling(): WordSampling
+{
+	return {
+		rarityBias: getSettings().rarityBias.get(),
+		missedBias: getSettings().missedBias.get(),
+		readingsBias: getSettings().readingsBias.get(),
+	};
+}
+
+const SEED_MODULUS = 2147483647;
+
+export function generateSeed(): number
+{
+	return Math.floor(Math.random() * (SEED_MODULUS - 1));
+}
+
+export function seedToCode(seed: number): string
+{
+	return seed.toString(36).toUpperCase();
+}
+
+export function seedFromCode(code: string): number | null
+{
+	const trimmed = code.trim().toLowerCase();
+	if (!/^[0-9a-z]+$/.test(trimmed)) return null;
+
+	return parseInt(trimmed, 36) % SEED_MODULUS;
+}
+
+const wordSequence = {
+	id: 0,
+	seed: null as number | null,
+	offset: 0,
+};
+
+// Restarts the word order of getRandomWord, a seeded sequence yields the same words in the same order every time
+export function startWordSequence(seed: number | null)
+{
+	wordSequence.id++;
+	wordSequence.seed = seed;
+	wordSequence.offset = 0;
+}
 
 export const getRandomWord = (() =>
 {
@@ -13,23 +71,48 @@ export const getRandomWord = (() =>
 	let lastSettingsKey: string | null = null;
 	let inFlight: Promise<void> | null = null;
 
-	const collectSettings = () => ({
-		minFrequency: getSettings().minFrequency.get(),
-		maxFrequency: getSettings().usingMaxFrequency.get() ?
-			getSettings().maxFrequency.get() :
-			null,
-		wordPart: getSettings().wordPart.get() || null,
-		wordPartReading: getSettings().wordPartReading.get() || null,
+	const collectSettings = (userKey: string) => ({
+		userKey,
+		reviewMode: getSettings().reviewMode.get(),
+		newWordsLimit: getSettings().newWordsLimit.get(),
+		filter: getWordFilter(),
+		sampling: getWordSampling(),
 		dictionaryId: getSettings().selectedDictionaryId.get(),
 		examplesCount: 5,
+		sequenceId: wordSequence.id,
+		seed: wordSequence.seed,
 	} as const);
 
 	const fetchBatch = async (settings: ReturnType<typeof collectSettings>, count: number) =>
 	{
+		if (settings.reviewMode)
+		{
+			const words: WordInfo[] = await getDueWords(
+				count,
+				settings.userKey,
+				settings.newWordsLimit,
+				settings.filter,
+				settings.examplesCount,
+				settings.dictionaryId,
+			);
+			cache.push(...words.filter(word => !cache.some(cached => cached.word === word.word)));
+			return;
+		}
+
 		const words: WordInfo[] = await invoke("get_words", {
-			...settings,
 			count,
+			filter: settings.filter,
+			sampling: settings.sampling,
+			seed: settings.seed,
+			offset: settings.seed != null ? wordSequence.offset : null,
+			userKey: settings.userKey,
+			examplesCount: settings.examplesCount,
+			dictionaryId: settings.dictionaryId,
 		});
+		if (settings.seed != null)
+		{
+			wordSequence.offset += words.length;
+		}
 		cache.push(...words);
 	};
 
@@ -45,15 +128,16 @@ export const getRandomWord = (() =>
 			});
 	};
 
-	return async function getRandomWord(): Promise<WordInfo>
+	return async function getRandomWord(userKey: string): Promise<WordInfo>
 	{
-		const settings = collectSettings();
+		const settings = collectSettings(userKey);
 		const settingsKey = JSON.stringify(settings);
 
 		if (settingsKey !== lastSettingsKey)
 		{
 			cache = [];
 			lastSettingsKey = settingsKey;
+			wordSequence.offset = 0;
 		}
 
 		if (cache.length === 0)
@@ -74,19 +158,60 @@ export const getRandomWord = (() =>
 			ensurePrefetch(settings);
 		}
 
-		const idx = Math.floor(Math.random() * cache.length);
+		const idx = settings.reviewMode || settings.seed != null ? 0 : Math.floor(Math.random() * cache.length);
 		const [word] = cache.splice(idx, 1);
 		return word;
 	};
 })();
 
+export async function getDueWords(
+	count: number,
+	userKey: string,
+	newWordsLimit: number,
+	filter: WordFilter,
+	examplesCount: number,
+	dictionaryId: number,
+): Promise<WordInfo[]>
+{
+	const data: WordInfo[] = await invoke("get_due_words", {
+		count: count,
+		userKey: userKey,
+		newWordsLimit: newWordsLimit,
+		filter: filter,
+		examplesCount: examplesCount,
+		dictionaryId: dictionaryId,
+	});
+	return data;
+}
+
+export async function searchWords(query: string, page: number, pageSize: number, examplesCount: number = 5): Promise<WordSearchPage>
+{
+	const data: WordSearchPage = await invoke("search_words", {
+		query: query,
+		page: page,
+		pageSize: pageSize,
+		examplesCount: examplesCount,
+		dictionaryId: getSettings().selectedDictionaryId.get(),
+	});
+	return data;
+}
+
+export async function getWordDetails(word: string, dictionaryId: number): Promise<WordInfo | null>
+{
+	const data: WordInfo | null = await invoke("get_word_details", { word, dictionaryId });
+	return data;
+}
+
+export async function getKanjiInfo(text: string, dictionaryId: number): Promise<KanjiInfo[]>
+{
+	const data: KanjiInfo[] = await invoke("get_kanji_info", { text, dictionaryId });
+	return data;
+}
+
 export async function getWordsCount(): Promise<number>
 {
 	const data: number = await invoke("get_words_count", {
-		minFrequency: getSettings().minFrequency.get(),
-		maxFrequency: getSettings().usingMaxFrequency.get() ? getSettings().maxFrequency.get() : null,
-		wordPart: getSettings().wordPart.get() == `` ? null : getSettings().wordPart.get(),
-		wordPartReading: getSettings().wordPartReading.get() == `` ? null : getSettings().wordPartReading.get(),
+		filter: getWordFilter(),
 		dictionaryId: getSettings().selectedDictionaryId.get(),
 	});
 
@@ -110,9 +235,9 @@ export async function addAnswerStats(
 	isCorrect: boolean,
 	roundIndex: number,
 	fontId: number,
-): Promise<void>
+): Promise<number>
 {
-	await invoke("add_answer_stats", {
+	return await invoke("add_answer_stats", {
 		gameStatsId: gameStatsId,
 		userKey: userKey,
 		userName: userName,
@@ -128,10 +253,10 @@ export async function addAnswerStats(
 export async function addGameStats(
 	roundsCount: number,
 	roundDuration: number,
-	minFrequency: number,
-	maxFrequency: number | null,
-	wordPart: string | null,
-	wordPartReading: string | null,
+	filter: WordFilter,
+	sampling: WordSampling,
+	seed: number | null,
+	isReviewMode: boolean,
 	fontId: number | null,
 	dictionaryId: number,
 ): Promise<number>
@@ -139,10 +264,10 @@ export async function addGameStats(
 	const index: number = await invoke("add_game_stats", {
 		roundsCount: roundsCount,
 		roundDuration: roundDuration,
-		minFrequency: minFrequency,
-		maxFrequency: maxFrequency,
-		wordPart: wordPart,
-		wordPartReading: wordPartReading,
+		filter: filter,
+		sampling: sampling,
+		seed: seed,
+		isReviewMode: isReviewMode,
 		fontId: fontId,
 		dictionaryId: dictionaryId,
 	});
@@ -234,6 +359,17 @@ export async function getAllUsers(): Promise<User[]>
 	return users;
 }
 
+export async function getDeletedDictionaries(): Promise<DeletedDictionary[]>
+{
+	const data: DeletedDictionary[] = await invoke("get_deleted_dictionaries");
+	return data;
+}
+
+export async function purgeDictionary(id: number, purgeToken: string): Promise<void>
+{
+	await invoke("purge_dictionary", { id, purgeToken });
+}
+
 export async function getDictionaries(): Promise<DictionaryInfo[]>
 {
 	const rawDictionaries: RawDictionaryInfo[] = await invoke("get_dictionaries");
@@ -266,19 +402,211 @@ export async function deleteDictionary(id: number): Promise<void>
 	console.log(x);
 }
 
-export async function importDictionary(dictPath: string): Promise<void>
+export async function importDictionary(dictPath: string): Promise<DictionaryImportSummary>
 {
-	const x = await invoke("import_dictionary", { dictPath });
-	console.log(x);
+	const data: DictionaryImportSummary = await invoke("import_dictionary", { dictPath });
+	return data;
+}
+
+export async function exportDictionary(id: number, path: string): Promise<void>
+{
+	await invoke("export_dictionary", { id, path });
+}
+
+export async function validateDictionary(dictPath: string): Promise<DictionaryValidationReport>
+{
+	const data: DictionaryValidationReport = await invoke("validate_dictionary", { dictPath });
+	return data;
+}
+
+export async function cancelDictionaryImport(): Promise<void>
+{
+	await invoke("cancel_dictionary_import");
+}
+
+export async function onDictionaryImportProgress(callback: (progress: ImportProgress) => void): Promise<UnlistenFn>
+{
+	return await listen<ImportProgress>("dictionary_import_progress", (event) => callback(event.payload));
+}
+
+export async function buildDictionary(
+	jmdictPath: string,
+	furiganaPath: string | null,
+	frequencyPath: string | null,
+	kanjidicPath: string | null,
+	pitchAccentPath: string | null,
+	outputPath: string,
+	name: string,
+	description: string | null,
+	filterTags: string[] | null = null,
+	excludeTags: string[] | null = null,
+): Promise<DictionaryBuildReport>
+{
+	const data: DictionaryBuildReport = await invoke("build_dictionary", {
+		jmdictPath: jmdictPath,
+		furiganaPath: furiganaPath,
+		frequencyPath: frequencyPath,
+		kanjidicPath: kanjidicPath,
+		pitchAccentPath: pitchAccentPath,
+		outputPath: outputPath,
+		name: name,
+		description: description,
+		filterTags: filterTags,
+		excludeTags: excludeTags,
+	});
+	return data;
+}
+
+export async function importWordList(path: string, name: string, description: string | null, dictionaryId: number | null = null): Promise<WordListImportReport>
+{
+	const data: WordListImportReport = await invoke("import_word_list", { path, name, description, dictionaryId });
+	return data;
+}
+
+export async function getAnkiNoteTypes(path: string): Promise<AnkiNoteType[]>
+{
+	const data: AnkiNoteType[] = await invoke("get_anki_note_types", { path });
+	return data;
+}
+
+export async function importAnkiDeck(
+	path: string,
+	name: string,
+	noteTypeId: number,
+	wordField: number,
+	readingField: number,
+	meaningField: number | null,
+	userKey: string | null,
+	dictionaryId: number | null = null,
+): Promise<AnkiImportReport>
+{
+	const data: AnkiImportReport = await invoke("import_anki_deck", {
+		path: path,
+		name: name,
+		noteTypeId: noteTypeId,
+		wordField: wordField,
+		readingField: readingField,
+		meaningField: meaningField,
+		userKey: userKey,
+		dictionaryId: dictionaryId,
+	});
+	return data;
+}
+
+export async function cancelDictionaryBuild(): Promise<void>
+{
+	await invoke("cancel_dictionary_build");
+}
+
+export async function onDictionaryBuildProgress(callback: (progress: BuildProgress) => void): Promise<UnlistenFn>
+{
+	return await listen<BuildProgress>("dictionary_build_progress", (event) => callback(event.payload));
 }
 
 export async function updateCardFsrs(
+	userKey: string,
+	dictionaryId: number,
 	word: string,
 	isCorrect: boolean,
+	duration: number | null,
+	roundDuration: number,
+	answerStatsId: number,
 ): Promise<void>
 {
 	await invoke("update_card_fsrs", {
+		userKey: userKey,
+		dictionaryId: dictionaryId,
 		word: word,
 		isCorrect: isCorrect,
+		duration: duration,
+		roundDuration: roundDuration,
+		answerStatsId: answerStatsId,
+	});
+}
+
+export async function getCardHistory(userKey: string, dictionaryId: number, word: string): Promise<ReviewLogEntry[]>
+{
+	const data: ReviewLogEntry[] = await invoke("get_card_history", {
+		userKey: userKey,
+		dictionaryId: dictionaryId,
+		word: word,
+	});
+	return data;
+}
+
+export async function getFsrsSettings(userKey: string): Promise<FsrsSettings>
+{
+	const data: FsrsSettings = await invoke("get_fsrs_settings", { userKey });
+	return data;
+}
+
+export async function setFsrsSettings(userKey: string, easyThreshold: number, hardThreshold: number): Promise<void>
+{
+	await invoke("set_fsrs_settings", {
+		userKey: userKey,
+		easyThreshold: easyThreshold,
+		hardThreshold: hardThreshold,
+	});
+}
+
+export async function optimizeFsrsParameters(userKey: string): Promise<FsrsOptimizationResult>
+{
+	const data: FsrsOptimizationResult = await invoke("optimize_fsrs_parameters", { userKey });
+	return data;
+}
+
+export async function getRetentionForecast(
+	userKey: string,
+	dictionaryId: number,
+	days: number,
+	targetDate: string | null,
+): Promise<RetentionForecast>
+{
+	const data: RetentionForecast = await invoke("get_retention_forecast", {
+		userKey: userKey,
+		dictionaryId: dictionaryId,
+		days: days,
+		targetDate: targetDate,
+	});
+	return data;
+}
+
+export async function rebuildCardsFsrs(userKey: string, dictionaryId: number, dryRun: boolean): Promise<CardRebuildReport>
+{
+	const data: CardRebuildReport = await invoke("rebuild_cards_fsrs", {
+		userKey: userKey,
+		dictionaryId: dictionaryId,
+		dryRun: dryRun,
+	});
+	return data;
+}
+
+export async function setCardSuspended(userKey: string, dictionaryId: number, word: string, isSuspended: boolean): Promise<void>
+{
+	await invoke("set_card_suspended", {
+		userKey: userKey,
+		dictionaryId: dictionaryId,
+		word: word,
+		isSuspended: isSuspended,
+	});
+}
+
+export async function buryCard(userKey: string, dictionaryId: number, word: string): Promise<void>
+{
+	await invoke("bury_card", { userKey, dictionaryId, word });
+}
+
+export async function resetCard(userKey: string, dictionaryId: number, word: string): Promise<void>
+{
+	await invoke("reset_card", { userKey, dictionaryId, word });
+}
+
+export async function setCardNote(userKey: string, dictionaryId: number, word: string, note: string | null): Promise<void>
+{
+	await invoke("set_card_note", {
+		userKey: userKey,
+		dictionaryId: dictionaryId,
+		word: word,
+		note: note,
 	});
 }
@@ -68,10 +68,18 @@ const settings = {
 	minFrequency: createStateVar(0),
 	maxFrequency: createStateVar(10000),
 	usingMaxFrequency: createStateVar(true),
+	minKanjiJlptN: createStateVar<number | null>(null),
+	maxKanjiGrade: createStateVar<number | null>(null),
 	roundDuration: createStateVar(10),
 	roundsCount: createStateVar(10),
+	gameSeed: createStateVar(``),
 	wordPart: createStateVar(``),
 	wordPartReading: createStateVar(``),
+	reviewMode: createStateVar(false),
+	newWordsLimit: createStateVar(10),
+	rarityBias: createStateVar(0),
+	missedBias: createStateVar(0),
+	readingsBias: createStateVar(0),
 	selectedFonts: createStateVar(new Array<string>()),
 	selectedDictionaryId: createStateVar(1),
 	currentAccount: createStateVar(0),
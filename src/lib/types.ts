@@ -7,6 +7,12 @@ export type RawDictionaryInfo = {
 	isExist: boolean;
 	statsConfig: string | null;
 	description: string | null;
+	version: string | null;
+	author: string | null;
+	license: string | null;
+	sourceUrl: string | null;
+	createdAt: string | null;
+	minSchemaVersion: number | null;
 };
 
 export type DictionaryInfo = {
@@ -16,8 +22,96 @@ export type DictionaryInfo = {
     isExist: boolean,
     statsConfig: DictionaryStatsConfig | null,
     description: string | null,
+    version: string | null,
+    author: string | null,
+    license: string | null,
+    sourceUrl: string | null,
+    createdAt: string | null,
+    minSchemaVersion: number | null,
 }
 
+export type DeletedDictionary = {
+	id: number;
+	guid: string;
+	name: string;
+	description: string | null;
+	gameStatsCount: number;
+	answerStatsCount: number;
+	cardsCount: number;
+	reviewLogCount: number;
+	purgeToken: string;
+};
+
+export type ValidationIssue = {
+	code: string;
+	message: string;
+	count: number;
+};
+
+export type DictionaryValidationReport = {
+	isValid: boolean;
+	errors: ValidationIssue[];
+	warnings: ValidationIssue[];
+};
+
+export type ImportStage = `deleteOld` | `dictionary` | `words` | `readings` | `partReadings` | `links` | `senses` | `kanji` | `wordKanji` | `searchIndex`;
+
+export type ImportProgress = {
+	stage: ImportStage;
+	rowsProcessed: number;
+	rowsTotal: number;
+};
+
+export type BuildStage = `jmdict` | `wordList` | `furigana` | `frequency` | `words` | `readings` | `partReadings` | `links` | `senses` | `kanjidic` | `kanji` | `readingTags` | `pitchAccent`;
+
+export type BuildProgress = {
+	stage: BuildStage;
+	processed: number;
+	total: number;
+};
+
+export type DictionaryBuildReport = {
+	guid: string;
+	entriesCount: number;
+	entriesSkipped: number;
+	wordsCount: number;
+	readingsCount: number;
+	partReadingsCount: number;
+	linksCount: number;
+	frequenciesCount: number;
+	pitchAccentsCount: number;
+	kanjiCount: number;
+};
+
+export type DictionaryImportSummary = {
+	wordsAdded: number;
+	wordsRemoved: number;
+	wordsChanged: number;
+	readingsAdded: number;
+	readingsRemoved: number;
+	readingsChanged: number;
+	warnings: string[];
+};
+
+export type WordListImportReport = {
+	dictionary: DictionaryBuildReport;
+	summary: DictionaryImportSummary;
+};
+
+export type AnkiNoteType = {
+	id: number;
+	name: string;
+	fields: string[];
+	notesCount: number;
+};
+
+export type AnkiImportReport = {
+	dictionary: DictionaryBuildReport;
+	summary: DictionaryImportSummary;
+	cardsCount: number;
+	reviewsCount: number;
+};
+
 export type GameStats = {
 	id: number;
 	roundsCount: number;
@@ -31,6 +125,10 @@ export type GameStats = {
 	realRoundsCount: number,
     usersCount: number,
 	timestamp: string;
+	wordFilter: string | null;
+	seed: number | null;
+	wordSampling: string | null;
+	isReviewMode: boolean;
 };
 
 export type AnswerStats = {
@@ -62,20 +160,158 @@ export type WordPartInfo = {
 
 export type ReadingWithParts = {
 	reading: string;
+	isCommon: boolean;
+	isRare: boolean;
+	isIrregular: boolean;
+	pitchAccents: number[];
 	parts: WordPartInfo[];
 };
 
+export type SenseInfo = {
+	glosses: string[];
+	pos: string[];
+	misc: string[];
+	field: string[];
+};
+
+export type WordEntry = {
+	priority: string[];
+	senses: SenseInfo[];
+};
+
 export type WordInfo = {
 	word: string;
 	meanings: string[][][];
+	entries: WordEntry[];
 	readings: ReadingWithParts[];
 };
 
+export type WordPartFilter = {
+	wordPart: string;
+	wordPartReading?: string | null;
+};
+
+export type WordFilter = {
+	minFrequency: number;
+	maxFrequency?: number | null;
+	wordParts?: WordPartFilter[];
+	excludeWordParts?: string[];
+	includeWords?: string[];
+	excludeWords?: string[];
+	minWordLength?: number | null;
+	maxWordLength?: number | null;
+	minReadingLength?: number | null;
+	maxReadingLength?: number | null;
+	minReadingsCount?: number | null;
+	maxReadingsCount?: number | null;
+	irregularReadings?: boolean | null;
+	minKanjiJlptN?: number | null;
+	maxKanjiGrade?: number | null;
+};
+
+export type WordSampling = {
+	rarityBias: number;
+	missedBias: number;
+	readingsBias: number;
+};
+
+export type KanjiInfo = {
+	kanji: string;
+	// JLPT level from before 2010, 4 (easiest) to 1
+	jlpt: number | null;
+	grade: number | null;
+	strokeCount: number | null;
+	onReadings: string[];
+	kunReadings: string[];
+	meanings: string[];
+};
+
+export type WordSearchPage = {
+	words: WordInfo[];
+	totalCount: number;
+};
+
 export type StatsInfo = {
 	correctCount: number;
 	wrongCount: number;
 };
 
+export type FsrsSettings = {
+	easyThreshold: number;
+	hardThreshold: number;
+	parameters: number[] | null;
+};
+
+export type FsrsOptimizationResult = {
+	parameters: number[];
+	reviewsCount: number;
+	logLossBefore: number;
+	logLossAfter: number;
+};
+
+export type ReviewLogEntry = {
+	id: number;
+	answerStatsId: number | null;
+	// 0 when the card was reset
+	rating: number;
+	elapsedDays: number;
+	previousState: number;
+	previousDue: string;
+	previousStability: number;
+	previousDifficulty: number;
+	state: number;
+	due: string;
+	stability: number;
+	difficulty: number;
+	scheduledDays: number;
+	reviewedAt: string;
+};
+
+export type DueDay = {
+	date: string;
+	count: number;
+};
+
+export type RetentionForecast = {
+	cardsCount: number;
+	reviewedCardsCount: number;
+	averageRetrievability: number | null;
+	duePerDay: DueDay[];
+	targetDate: string;
+	expectedRetention: number | null;
+};
+
+export type CardFsrsRecord = {
+	id: number | null;
+	word: string;
+	due: string;
+	stability: number;
+	difficulty: number;
+	elapsedDays: number;
+	scheduledDays: number;
+	reps: number;
+	lapses: number;
+	state: number;
+	lastReview: string;
+};
+
+export type CardRebuildDiff = {
+	word: string;
+	change: `added` | `removed` | `changed`;
+	current: CardFsrsRecord | null;
+	rebuilt: CardFsrsRecord | null;
+};
+
+export type CardRebuildReport = {
+	dryRun: boolean;
+	reviewsCount: number;
+	addedCount: number;
+	removedCount: number;
+	changedCount: number;
+	unchangedCount: number;
+	diffs: CardRebuildDiff[];
+};
+
 export type AnswerStreaks = {
 	gameId: number;
 	length: number;
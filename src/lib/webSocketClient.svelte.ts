@@ -1,7 +1,7 @@
 import { getSettings } from "$lib/globalSettings.svelte";
 import { ServerConnector } from "$lib/webSocketConnector";
 import { SvelteMap } from "svelte/reactivity";
-import { addAnswerStats, addGameStats, getAnswerStatsByGame, getDictionaries, getFontId, getGameStats, getRandomWord, updateCardFsrs } from "./databaseTools";
+import { addAnswerStats, addGameStats, getAnswerStatsByGame, getDictionaries, generateSeed, getFontId, getGameStats, getRandomWord, getWordFilter, getWordSampling, seedFromCode, startWordSequence, updateCardFsrs } from "./databaseTools";
 import { getDefaultFont, getFontInfo, getSVGText } from "./fontTools";
 import { getAccounts, signMessage } from "./networkTools";
 import type {
@@ -42,6 +42,8 @@ class WebSocketClient
 	public accountKey: string = $state(``);
 	public fontsInfo: Array<FontInfo> = new Array<FontInfo>();
 	public isBusy: boolean = $state(false);
+	public busyMessage: string = $state(``);
+	public busyCancel: (() => void) | null = $state(null);
 
 	public onlineWordPart: string = $state(`(no option)`)
 	public onlineWordPartReading: string = $state(`(no option)`)
@@ -311,8 +313,15 @@ class WebSocketClient
         this.gameStatus = `WaitingQuestion`;
 
 		let fontID: number | null = null;
+		let seed: number | null = null;
 		if (this.isConnectedToSelf)
 		{
+			if (!getSettings().reviewMode.get())
+			{
+				seed = seedFromCode(getSettings().gameSeed.get()) ?? generateSeed();
+			}
+			startWordSequence(seed);
+
 			const fontsCount = getSettings().selectedFonts.get().length;
 			if (fontsCount == 1)
 			{
@@ -329,10 +338,15 @@ class WebSocketClient
 		this.lastGameId = await addGameStats(
 			customEvent.detail.gameSettings.roundsCount,
 			customEvent.detail.gameSettings.roundDuration * 1000,
-			getSettings().minFrequency.get(),
-			getSettings().usingMaxFrequency.get() ? getSettings().maxFrequency.get() : null,
-			customEvent.detail.gameSettings.wordPart,
-			customEvent.detail.gameSettings.wordPartReading,
+			{
+				...getWordFilter(),
+				wordParts: customEvent.detail.gameSettings.wordPart ?
+					[{ wordPart: customEvent.detail.gameSettings.wordPart, wordPartReading: customEvent.detail.gameSettings.wordPartReading }] :
+					[],
+			},
+			getWordSampling(),
+			seed,
+			this.isConnectedToSelf && getSettings().reviewMode.get(),
 			fontID,
 			getSettings().selectedDictionaryId.get(),
 		);
@@ -347,7 +361,7 @@ class WebSocketClient
 		{
 			try
 			{
-				const lastWord = await getRandomWord();
+				const lastWord = await getRandomWord(this.accountKey);
 				let font: string;
 				if (getSettings().selectedFonts.get().length > 0)
 				{
@@ -390,7 +404,7 @@ class WebSocketClient
 		const customEvent: CustomEvent<OutNotifQuestionPayload> = <CustomEvent<OutNotifQuestionPayload>> event;
 		this.gameHistory.push({
 			question: {
-				wordInfo: { word: ``, meanings: [], readings: [] },
+				wordInfo: { word: ``, meanings: [], entries: [], readings: [] },
 				fontName: ``,
 			},
 			questionSvg: customEvent.detail.questionSvg,
@@ -444,6 +458,7 @@ class WebSocketClient
 		const roundNumber = this.currentRound - 1;
 		const clients = [...this.clientList];
 		const answersMap = new Map(lastRound.answers);
+		const gameStats = await getGameStats(gameId);
 
 		const fontId = await getFontId(question.fontName);
 
@@ -455,7 +470,7 @@ class WebSocketClient
 				const ans = answersMap.get(client.id);
 				if (!ans) return null;
 
-				await addAnswerStats(
+				const answerStatsId = await addAnswerStats(
 					gameId,
 					client.key,
 					client.name,
@@ -468,8 +483,13 @@ class WebSocketClient
 				);
 
 				await updateCardFsrs(
+					client.key,
+					gameStats.dictionaryId,
 					word,
 					ans.answerStatus === "Correct",
+					ans.answerTime,
+					gameStats.roundDuration,
+					answerStatsId,
 				);
 			}),
 		);
@@ -517,13 +537,14 @@ class WebSocketClient
 		});
 
 		const fontId = await getFontId(customEvent.detail.question.fontName);
+		const gameStats = await getGameStats(this.lastGameId);
 
 		const answerPromises = this.clientList.map(async (client) =>
 		{
 			const answer = lastRoundHistory.answers.get(client.id);
 			if (answer)
 			{
-				await addAnswerStats(
+				const answerStatsId = await addAnswerStats(
 					this.lastGameId,
 					client.key,
 					client.name,
@@ -535,10 +556,15 @@ class WebSocketClient
 					fontId,
 				);
 
-				// await updateCardFsrs(
-				// 	lastRoundHistory.question.wordInfo.word,
-				// 	answer.answerStatus == `Correct`,
-				// );
+				await updateCardFsrs(
+					client.key,
+					gameStats.dictionaryId,
+					lastRoundHistory.question.wordInfo.word,
+					answer.answerStatus == `Correct`,
+					answer.answerTime,
+					gameStats.roundDuration,
+					answerStatsId,
+				);
 			}
 		});
 